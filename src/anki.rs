@@ -0,0 +1,63 @@
+use std::fs;
+use std::io;
+use crate::glossary::{self, Term};
+use crate::output::escape;
+use crate::quiz::{self, Kind, Question};
+
+//Every quiz question and every glossary term as a tab-separated file Anki can import (File > Import),
+//one note per line. Questions are tagged with their module, terms with "glossary" and the module
+//that introduces them. Fields are HTML, so code keeps its layout in a <pre> block
+
+struct Note {
+    front: String,
+    back: String,
+    //the module it's about: "slice", "references_and_borrowing", ... (and "glossary" for terms)
+    tags: String,
+}
+
+//Returns how many notes were written, questions and terms together
+pub fn export(path: &str) -> io::Result<usize> {
+    let mut notes: Vec<Note> = quiz::questions(None).iter().map(note).collect();
+    notes.extend(glossary::TERMS.iter().map(term_note));
+
+    //the # lines tell Anki how to read the rest, so nothing has to be set up when importing
+    let mut text = String::from("#separator:tab\n#html:true\n#tags column:3\n");
+    for note in &notes {
+        text += &format!("{}\t{}\t{}\n", note.front, note.back, note.tags);
+    }
+    fs::write(path, text)?;
+    Ok(notes.len())
+}
+
+fn note(question: &Question) -> Note {
+    let mut front = escape(&question.prompt);
+    if let Some(code) = &question.code {
+        front += &pre(code);
+    }
+    match &question.kind {
+        Kind::Choice { options, .. } => {
+            let options: Vec<String> = options.iter().map(|o| format!("<li>{}</li>", escape(o))).collect();
+            front += &format!("<ol type=\"a\">{}</ol>", options.join(""));
+        }
+        Kind::TrueFalse(_) => front += "<br>(true or false)",
+        Kind::Text(_) => {}
+    }
+    let back = format!("<b>{}</b><br><br>{}", escape(&question.answer()), escape(&question.why));
+    Note { front, back, tags: question.module().to_string() }
+}
+
+fn term_note(term: &Term) -> Note {
+    let mut back = escape(term.definition);
+    if let Some(example) = term.example {
+        back += &pre(example);
+    }
+    back += &format!("<br><i>Introduced in {}</i>", term.introduced);
+    let module = term.introduced.split("::").next().unwrap_or_default();
+    Note { front: escape(term.name), back, tags: format!("glossary {module}") }
+}
+
+//A field can't have a real newline (or tab) in it, so lines are broken with <br> instead
+fn pre(code: &str) -> String {
+    let lines: Vec<String> = code.lines().map(|l| escape(l).replace('\t', "    ")).collect();
+    format!("<pre>{}</pre>", lines.join("<br>"))
+}
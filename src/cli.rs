@@ -0,0 +1,152 @@
+//Parses the command line. No command at all means "show the menu"
+pub struct Cli {
+    pub command: Command,
+    //answers file for lessons (and the menu) instead of the terminal
+    pub input: Option<String>,
+    //transcript (or .html page) to write lessons to instead of the terminal
+    pub output: Option<String>,
+    //fixes every random choice a lesson makes
+    pub seed: Option<u64>,
+    pub no_diagnostics: bool,
+    //directory to write every memory diagram to as a Graphviz .dot file
+    pub dot: Option<String>,
+    //whose progress to load and save
+    pub learner: Option<String>,
+}
+
+pub enum Command {
+    Menu,
+    List,
+    Run(String),
+    RunAll,
+    Show(String),
+    //None means "type a program in"
+    Toy(Option<String>),
+    ToyCheck(Option<String>),
+    ToyStep(Option<String>),
+    ToyList,
+    //None means every module
+    Quiz(Option<String>),
+    QuizList,
+    Review,
+    Compiles(usize),
+    //None means list every term
+    Glossary(Option<String>),
+    Search(String),
+    //file to write the Anki notes to
+    Anki(String),
+    Help,
+}
+
+pub const USAGE: &str = "\
+usage: ownership [options] [command]
+
+commands:
+  (none)             pick lessons from a menu
+  list               list every lesson
+  run <lesson>       run one lesson, e.g. `run ownership_case_study::case3`
+  run --all          run every lesson, in the recommended order
+  show <lesson>      show what a lesson is about and its source
+  toy [example|file] run a program in the toy language (type one in if there's no argument)
+  toy check [example|file]
+                     borrow check a toy program instead of running it
+  toy step [example|file]
+                     run a toy program one statement at a time (next, back, inspect <variable>)
+  toy list           list the toy language's examples
+  quiz [module]      answer questions about every lesson module, or just one
+  quiz list          list the modules that have questions
+  review             go over the quiz questions that are due again
+  compiles [rounds]  guess whether case study code compiles, then see what rustc says (10 rounds)
+  glossary [term]    look up a term the lessons use, or list them all
+  search <query>     find where the lessons, glossary and quiz talk about something
+  anki <file>        export the quiz questions and glossary terms as Anki notes (a tab-separated file)
+  help               show this message
+
+options:
+  --input <file>     read answers from a file (one per line) instead of the terminal
+  --output <file>    write lessons to a transcript file (a web page if it ends in .html)
+  --seed <number>    make the lessons' random choices the same on every run
+  --no-diagnostics   don't run rustc to show the real errors, just name them
+  --dot <dir>        also write every memory diagram to <dir> as a Graphviz .dot file
+  --learner <name>   keep progress under this name instead of your user name";
+
+pub fn parse(args: &[String]) -> Result<Cli, String> {
+    let mut input = None;
+    let mut output = None;
+    let mut seed = None;
+    let mut no_diagnostics = false;
+    let mut dot = None;
+    let mut learner = None;
+    let mut rest: Vec<&str> = Vec::new();
+
+    //Options can go anywhere, everything else is the command
+    let mut args = args.iter().map(|a| a.as_str());
+    while let Some(arg) = args.next() {
+        match arg {
+            "--input" => {
+                let file = args.next().ok_or("`--input` needs a file")?;
+                input = Some(file.to_string());
+            }
+            "--output" => {
+                let file = args.next().ok_or("`--output` needs a file")?;
+                output = Some(file.to_string());
+            }
+            "--seed" => {
+                let n = args.next().ok_or("`--seed` needs a number")?;
+                let n = n.parse().map_err(|_| format!("`--seed` needs a number, not `{n}`"))?;
+                seed = Some(n);
+            }
+            "--no-diagnostics" => no_diagnostics = true,
+            "--learner" => {
+                let name = args.next().ok_or("`--learner` needs a name")?;
+                if name.is_empty() || name.contains(char::is_whitespace) {
+                    return Err(format!("`--learner` needs a name without spaces, not `{name}`"));
+                }
+                learner = Some(name.to_string());
+            }
+            "--dot" => {
+                let dir = args.next().ok_or("`--dot` needs a directory")?;
+                dot = Some(dir.to_string());
+            }
+            _ => rest.push(arg),
+        }
+    }
+
+    Ok(Cli { command: parse_command(&rest)?, input, output, seed, no_diagnostics, dot, learner })
+}
+
+fn parse_command(args: &[&str]) -> Result<Command, String> {
+    match args {
+        [] => Ok(Command::Menu),
+        ["list"] => Ok(Command::List),
+        ["run", "--all"] => Ok(Command::RunAll),
+        ["run", lesson] => Ok(Command::Run(lesson.to_string())),
+        ["run"] => Err(String::from("`run` needs a lesson name (or --all)")),
+        ["show", lesson] => Ok(Command::Show(lesson.to_string())),
+        ["show"] => Err(String::from("`show` needs a lesson name")),
+        ["toy"] => Ok(Command::Toy(None)),
+        ["compiles"] => Ok(Command::Compiles(10)),
+        ["compiles", n] => match n.parse() {
+            Ok(rounds) => Ok(Command::Compiles(rounds)),
+            Err(_) => Err(format!("`compiles` needs a number of rounds, not `{n}`")),
+        },
+        ["review"] => Ok(Command::Review),
+        ["glossary"] => Ok(Command::Glossary(None)),
+        ["glossary", term @ ..] => Ok(Command::Glossary(Some(term.join(" ")))),
+        ["search"] => Err(String::from("`search` needs something to search for")),
+        ["search", query @ ..] => Ok(Command::Search(query.join(" "))),
+        ["anki", file] => Ok(Command::Anki(file.to_string())),
+        ["anki"] => Err(String::from("`anki` needs a file to write to")),
+        ["quiz"] => Ok(Command::Quiz(None)),
+        ["quiz", "list"] => Ok(Command::QuizList),
+        ["quiz", module] => Ok(Command::Quiz(Some(module.to_string()))),
+        ["toy", "list"] => Ok(Command::ToyList),
+        ["toy", "check"] => Ok(Command::ToyCheck(None)),
+        ["toy", "check", program] => Ok(Command::ToyCheck(Some(program.to_string()))),
+        ["toy", "step"] => Ok(Command::ToyStep(None)),
+        ["toy", "step", program] => Ok(Command::ToyStep(Some(program.to_string()))),
+        ["toy", program] => Ok(Command::Toy(Some(program.to_string()))),
+        ["help"] | ["--help"] | ["-h"] => Ok(Command::Help),
+        _ => Err(format!("don't know what to do with `{}`", args.join(" "))),
+    }
+}
@@ -0,0 +1,191 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use crate::diagnostics::{self, SNIPPETS};
+use crate::lesson::Context;
+use crate::output::say;
+use crate::source;
+
+//"Does this compile?": code from the case study, its faux solutions and small random changes to them.
+//The learner guesses, then the local rustc decides (cached, like the lessons' diagnostics)
+
+//Case study functions that make sense on their own, outside the lesson
+const FUNCTIONS: [&str; 16] = [
+    "solution1_1",
+    "solution1_2",
+    "solution1_4",
+    "stringify_name_with_title",
+    "faux_solution2_1",
+    "faux_solution2_2",
+    "solution2_1",
+    "solution2_2",
+    "add_big_strings",
+    "semi_solution3_1",
+    "semi_solution3_2",
+    "solution3",
+    "safe_copy",
+    "unsafe_copy",
+    "solution4_2",
+    "solution4_3",
+];
+
+struct Snippet {
+    //also the file rustc compiles it as, so it has to be a valid crate name
+    name: String,
+    code: String,
+    //"case4": where the case study explains it
+    case: Option<String>,
+}
+
+pub fn play(ctx: &mut Context, rounds: usize) {
+    let pool = pool();
+    let (mut right, mut asked) = (0, 0);
+
+    for round in 1..=rounds {
+        let mut snippet = pool.choose(&mut ctx.rng).expect("there are always snippets");
+        //a third of the time, change something first
+        let changed;
+        if ctx.rng.gen_ratio(1, 3) {
+            if let Some(mutated) = mutate(ctx, snippet) {
+                changed = mutated;
+                snippet = &changed;
+            }
+        }
+
+        say!(ctx, "\nRound {round} of {rounds}: does this compile? (y or n)\n");
+        for line in snippet.code.lines() {
+            say!(ctx, "    {line}");
+        }
+        let Some(guess) = guess(ctx) else { break };
+
+        let Some(verdict) = diagnostics::check(&snippet.name, &snippet.code) else {
+            say!(ctx, "(couldn't run rustc, so this round doesn't count)");
+            continue;
+        };
+        asked += 1;
+        right += (guess == verdict.compiled) as usize;
+        let mark = if guess == verdict.compiled { "Right" } else { "Nope" };
+        if verdict.compiled {
+            say!(ctx, "{mark}: it compiles.");
+            continue;
+        }
+
+        let errors: Vec<&str> = verdict.diagnostic.lines().filter(|l| l.starts_with("error")).collect();
+        let codes = codes_of(&errors);
+        let named = if codes.is_empty() { String::from("an error") } else { codes.join(", ") };
+        say!(ctx, "{mark}: rustc rejects it with {named}.");
+        if let Some(first) = errors.first() {
+            say!(ctx, "    {first}");
+        }
+        if let Some(case) = explained_in(snippet, &codes) {
+            say!(ctx, "It's explained in {case}: `ownership run ownership_case_study::{case}`");
+        }
+    }
+
+    say!(ctx);
+    match asked {
+        0 => say!(ctx, "No rounds played."),
+        _ => say!(ctx, "You got {right} of {asked} right."),
+    }
+}
+
+//y or n, asking again until it's one of them. None to stop playing (q, or no more input)
+fn guess(ctx: &mut Context) -> Option<bool> {
+    loop {
+        let reply = ctx.input.read_line()?;
+        match reply.trim() {
+            "q" | "quit" => return None,
+            "y" | "yes" => return Some(true),
+            "n" | "no" => return Some(false),
+            other => say!(ctx, "'{other}' isn't y or n (or q to stop playing)."),
+        }
+    }
+}
+
+//"error[E0502]: cannot borrow..." -> "E0502"
+fn codes_of<'a>(errors: &[&'a str]) -> Vec<&'a str> {
+    errors.iter().filter_map(|l| l.strip_prefix("error[")?.split_once(']')).map(|(code, _)| code).collect()
+}
+
+//The case to read about it, but only when rustc rejected it for something that case teaches.
+//A change can break code in a way no case is about, like removing a .clone() that fixed a type
+fn explained_in<'a>(snippet: &'a Snippet, codes: &[&str]) -> Option<&'a str> {
+    let case = snippet.case.as_deref()?;
+    let teaches = teaches(case);
+    codes.iter().any(|code| teaches.contains(code)).then_some(case)
+}
+
+//The errors in a case's compile-fail fixtures
+fn teaches(case: &str) -> Vec<&'static str> {
+    SNIPPETS
+        .iter()
+        .filter(|f| f.from().strip_prefix("ownership_case_study::").and_then(case_of).as_deref() == Some(case))
+        .flat_map(|f| f.codes())
+        .collect()
+}
+
+//The case study's compile-fail fixtures, and its functions as they are in the lesson
+fn pool() -> Vec<Snippet> {
+    let mut pool = Vec::new();
+    for fixture in &SNIPPETS {
+        let Some(from) = fixture.from().strip_prefix("ownership_case_study::") else { continue };
+        //the header says what's wrong with it, so it stays hidden
+        let code: Vec<&str> = fixture.source.lines().skip_while(|l| l.starts_with("//") || l.is_empty()).collect();
+        pool.push(Snippet { name: fixture.name.to_string(), code: code.join("\n"), case: case_of(from) });
+    }
+    for name in FUNCTIONS {
+        if let Some(code) = source::function(&format!("ownership_case_study::{name}")) {
+            pool.push(Snippet { name: name.to_string(), code: code.to_string(), case: case_of(name) });
+        }
+    }
+    pool
+}
+
+//Which caseN a case study function belongs to: each one comes after its case's pub fn
+fn case_of(function: &str) -> Option<String> {
+    let src = source::file("ownership_case_study")?;
+    let at = src.find(source::function(&format!("ownership_case_study::{function}"))?)?;
+    let case = &src[src[..at].rfind("pub fn case")? + "pub fn ".len()..];
+    Some(case[..case.find('(')?].to_string())
+}
+
+//One small change that might (or might not) break it
+fn mutate(ctx: &mut Context, snippet: &Snippet) -> Option<Snippet> {
+    let lines: Vec<&str> = snippet.code.lines().collect();
+    let mut changes: Vec<(usize, String)> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let code = line.trim_start();
+        //a line of code the lesson commented out because it doesn't compile
+        if let Some(uncommented) = code.strip_prefix("//").filter(|c| c.trim_end().ends_with(';')) {
+            changes.push((i, line.replacen(code, uncommented, 1)));
+        }
+        if line.contains(".clone()") {
+            changes.push((i, line.replacen(".clone()", "", 1)));
+        }
+        if line.contains("&mut ") {
+            changes.push((i, line.replacen("&mut ", "&", 1)));
+        }
+        if code.starts_with("let mut ") {
+            changes.push((i, line.replacen("let mut ", "let ", 1)));
+        }
+    }
+
+    let (at, changed) = changes.choose(&mut ctx.rng)?.clone();
+    let code: Vec<&str> = lines.iter().enumerate().map(|(i, l)| if i == at { changed.as_str() } else { l }).collect();
+    Some(Snippet { name: format!("{}_changed", snippet.name), code: code.join("\n"), case: snippet.case.clone() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_errors_the_case_teaches_link_to_it() {
+        let code = source::function("ownership_case_study::add_big_strings").unwrap().replacen(".clone()", "", 1);
+        let snippet = Snippet { name: String::from("add_big_strings_changed"), code, case: case_of("add_big_strings") };
+        assert_eq!(snippet.case.as_deref(), Some("case3"));
+        assert_eq!(explained_in(&snippet, &["E0502"]), Some("case3"));
+        //taking the .clone() out of add_big_strings is a type mismatch, which case3 isn't about
+        assert_eq!(explained_in(&snippet, &["E0308"]), None);
+        assert_eq!(explained_in(&snippet, &[]), None);
+    }
+}
@@ -0,0 +1,169 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+use crate::lesson::Context;
+use crate::output::say;
+use crate::paths;
+
+//A piece of code that doesn't compile, straight from the compile-fail fixtures (tests/compile_fail).
+//The header says where it's from and which errors rustc gives:
+//    // from: ownership::explain_rust_memory
+//    // error: E0382
+pub struct Snippet {
+    pub name: &'static str,
+    pub source: &'static str,
+}
+
+macro_rules! snippet {
+    ($name:literal) => {
+        Snippet {
+            name: $name,
+            source: include_str!(concat!("../tests/compile_fail/", $name, ".rs")),
+        }
+    };
+}
+
+pub const SNIPPETS: [Snippet; 17] = [
+    snippet!("add_big_strings"),
+    snippet!("array_read_write"),
+    snippet!("assign_while_reborrowed"),
+    snippet!("drop_while_borrowed"),
+    snippet!("flow"),
+    snippet!("get_first"),
+    snippet!("greet_after_move"),
+    snippet!("push_while_borrowed"),
+    snippet!("read_vec_while_god_ref_lives"),
+    snippet!("read_while_mutably_borrowed"),
+    snippet!("return_a_string"),
+    snippet!("return_through_reference"),
+    snippet!("slice_after_clear"),
+    snippet!("stringify_name_with_title"),
+    snippet!("unsafe_copy"),
+    snippet!("use_after_move"),
+    snippet!("use_of_moved_clone"),
+];
+
+impl Snippet {
+    //The error codes rustc is expected to give, like "E0382"
+    pub fn codes(&self) -> Vec<&'static str> {
+        self.header("error").collect()
+    }
+
+    //The lesson function it's from, like "ownership::explain_rust_memory"
+    pub fn from(&self) -> &'static str {
+        self.header("from").next().unwrap_or_default()
+    }
+
+    fn header(&self, key: &str) -> impl Iterator<Item = &'static str> {
+        let prefix = format!("// {key}: ");
+        self.source
+            .lines()
+            .take_while(|line| line.starts_with("//"))
+            .filter_map(move |line| line.strip_prefix(prefix.as_str()))
+            .map(str::trim)
+    }
+}
+
+pub fn snippet(name: &str) -> Option<&'static Snippet> {
+    SNIPPETS.iter().find(|s| s.name == name)
+}
+
+//What rustc said about a piece of code
+pub struct Verdict {
+    pub compiled: bool,
+    pub diagnostic: String,
+}
+
+//Shows what the real compiler says about a snippet, right where the lesson talks about it.
+//With --no-diagnostics (or no rustc around) it just names the error the lesson expects
+pub fn show(ctx: &mut Context, name: &str) {
+    let snippet = snippet(name).unwrap_or_else(|| panic!("there's no snippet called {name}"));
+    let codes = snippet.codes().join(", ");
+
+    if !ctx.diagnostics {
+        say!(ctx, "    (rustc rejects this with {codes}, run without --no-diagnostics to see the whole error)");
+        return;
+    }
+
+    match check(name, snippet.source) {
+        Some(verdict) => {
+            say!(ctx, "    rustc says:");
+            for line in verdict.diagnostic.lines() {
+                say!(ctx, "    | {line}");
+            }
+        }
+        None => say!(ctx, "    (couldn't run rustc, but it rejects this with {codes})"),
+    }
+}
+
+//Compiles the code with the local rustc. Results are cached by a hash of the code and rustc's version,
+//so asking again is instant (and works without a toolchain once it's cached)
+pub fn check(name: &str, source: &str) -> Option<Verdict> {
+    let dir = paths::cache_dir().join("diagnostics");
+    let cached = dir.join(format!("{:016x}.txt", hash(&format!("{}\n{source}", rustc_version(&dir)))));
+
+    if let Ok(text) = fs::read_to_string(&cached) {
+        if let Some((status, diagnostic)) = text.split_once('\n') {
+            return Some(Verdict { compiled: status == "ok", diagnostic: diagnostic.to_string() });
+        }
+    }
+
+    let verdict = compile(&dir, name, source)?;
+    let status = if verdict.compiled { "ok" } else { "error" };
+    //Not being able to cache just means compiling again next time
+    let _ = fs::write(&cached, format!("{status}\n{}", verdict.diagnostic));
+    Some(verdict)
+}
+
+fn compile(dir: &Path, name: &str, source: &str) -> Option<Verdict> {
+    //Each snippet gets a file named after it so the diagnostic says "--> use_after_move.rs:14:16"
+    let work = dir.join("work");
+    fs::create_dir_all(&work).ok()?;
+    let file = format!("{name}.rs");
+    fs::write(work.join(&file), source).ok()?;
+
+    let out = Command::new("rustc")
+        .args(["--edition", "2021", "--crate-type", "lib", "--emit", "metadata", "--color", "never"])
+        .arg("--crate-name")
+        .arg(name)
+        .arg(&file)
+        .current_dir(&work)
+        .output()
+        .ok()?;
+
+    //The "aborting due to..." and "try rustc --explain" lines at the end are just noise in a lesson
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    let diagnostic: Vec<&str> = stderr
+        .lines()
+        .filter(|line| !line.starts_with("error: aborting due to") && !line.starts_with("For more information about"))
+        .collect();
+
+    Some(Verdict {
+        compiled: out.status.success(),
+        diagnostic: diagnostic.join("\n").trim_end().to_string(),
+    })
+}
+
+//`rustc -V`, so a new toolchain doesn't get the old one's diagnostics. With no rustc around
+//it's the last version seen, so what was cached still works offline
+fn rustc_version(dir: &Path) -> &'static str {
+    static VERSION: OnceLock<String> = OnceLock::new();
+    VERSION.get_or_init(|| {
+        let seen = dir.join("rustc-version.txt");
+        match Command::new("rustc").arg("-V").output() {
+            Ok(out) if out.status.success() => {
+                let version = String::from_utf8_lossy(&out.stdout).trim().to_string();
+                let _ = fs::create_dir_all(dir);
+                let _ = fs::write(&seen, &version);
+                version
+            }
+            _ => fs::read_to_string(&seen).unwrap_or_default(),
+        }
+    })
+}
+
+//FNV-1a: tiny, and unlike DefaultHasher it's guaranteed to give the same hash on every run
+fn hash(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3))
+}
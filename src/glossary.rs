@@ -0,0 +1,289 @@
+use crate::lesson::Context;
+use crate::output::say;
+use crate::source;
+
+//The terms the lessons bring up in passing, each with where it first comes up.
+//When lessons print to a terminal, these get highlighted (see highlight)
+
+pub struct Term {
+    pub name: &'static str,
+    //other ways the lessons write it, which are also highlighted and can be looked up
+    pub also: &'static [&'static str],
+    pub definition: &'static str,
+    pub example: Option<&'static str>,
+    //the lesson function it's introduced in, and the lesson that runs it
+    pub introduced: &'static str,
+    pub lesson: &'static str,
+    pub see: &'static [&'static str],
+}
+
+pub const TERMS: [Term; 17] = [
+    Term {
+        name: "undefined behavior",
+        also: &["undefined behaviors"],
+        definition: "What a program does when it breaks the language's rules, like using memory that was already freed. \
+            Anything can happen, so a safe program is one where every behavior is defined.",
+        example: None,
+        introduced: "ownership::introduce",
+        lesson: "ownership",
+        see: &["ownership"],
+    },
+    Term {
+        name: "ownership",
+        also: &["owner", "owned"],
+        definition: "Every value on the heap has exactly one variable that owns it, and it's freed when that owner goes away. \
+            Rust checks this at compile time, so there's no garbage collector and no double free.",
+        example: Some("let no_im_not = String::from(\"nine\");  //no_im_not owns \"nine\""),
+        introduced: "ownership::explain_ownership",
+        lesson: "ownership",
+        see: &["move", "borrowing"],
+    },
+    Term {
+        name: "move",
+        also: &["moves", "moved"],
+        definition: "Handing ownership to another variable (or into a function). The old variable can't be used after that.",
+        example: Some("let stupid = add_ten(no_im_not);  //no_im_not moved into add_ten\n//println!(\"{no_im_not}\");  E0382"),
+        introduced: "ownership::explain_rust_memory",
+        lesson: "ownership",
+        see: &["ownership", "Copy trait"],
+    },
+    Term {
+        name: "reference",
+        also: &["references"],
+        definition: "A pointer that doesn't own what it points to, written &. When it goes away nothing is freed.",
+        example: Some("fn emphasize(word: &String)"),
+        introduced: "references_and_borrowing::explain_references",
+        lesson: "references_and_borrowing",
+        see: &["borrowing", "dereference", "unique reference"],
+    },
+    Term {
+        name: "borrowing",
+        also: &["borrow", "borrows", "borrowed"],
+        definition: "Using a reference instead of taking ownership. The owner gets its value back when the reference is done with it.",
+        example: Some("emphasize(ctx, &input);  //input is borrowed, and still usable afterwards"),
+        introduced: "references_and_borrowing::emphasize",
+        lesson: "references_and_borrowing",
+        see: &["reference", "borrow checker"],
+    },
+    Term {
+        name: "dereference",
+        also: &["dereferences", "dereferencing"],
+        definition: "Following a pointer to the data it points to, written *. The dot operator does it for you (x.abs() is i32::abs(*x)).",
+        example: Some("let c: &Box<i32> = &a;\nlet d: i32 = **c;  //c to a, then a to the heap"),
+        introduced: "references_and_borrowing::explain_dereferencing",
+        lesson: "references_and_borrowing",
+        see: &["reference", "dereference coercion"],
+    },
+    Term {
+        name: "aliasing",
+        also: &["aliased", "alias"],
+        definition: "Reaching the same data through more than one variable. It's only a problem together with mutation.",
+        example: Some("let num: &i32 = &vec[2];  //num and vec both reach vec[2]"),
+        introduced: "references_and_borrowing::explain_aliasing",
+        lesson: "references_and_borrowing",
+        see: &["Pointer Safety Principle"],
+    },
+    Term {
+        name: "Pointer Safety Principle",
+        also: &[],
+        definition: "Data should never be aliased and mutated at the same time. \
+            Boxes keep it by never being aliased, references by losing permissions while they're borrowed.",
+        example: Some("let num: &i32 = &vec[2];\nvec.push(4);  //could reallocate while num points into vec: E0502"),
+        introduced: "references_and_borrowing::explain_aliasing",
+        lesson: "references_and_borrowing",
+        see: &["aliasing", "borrow checker"],
+    },
+    Term {
+        name: "borrow checker",
+        also: &[],
+        definition: "The part of the compiler that keeps the Pointer Safety Principle, by checking every place's permissions on every line.",
+        example: None,
+        introduced: "references_and_borrowing::explain_aliasing",
+        lesson: "references_and_borrowing",
+        see: &["permissions", "Pointer Safety Principle"],
+    },
+    Term {
+        name: "permissions",
+        also: &["permission", "perms"],
+        definition: "What can be done with a place right now: R (read), W (write), O (own) and F (flow). \
+            Borrowing takes some of them away until the reference's last use.",
+        example: Some("let num: &i32 = &vec[2];  //vec -W -O, num +R +O, *num +R"),
+        introduced: "references_and_borrowing::explain_aliasing",
+        lesson: "references_and_borrowing",
+        see: &["borrow checker", "Flow permission"],
+    },
+    Term {
+        name: "unique reference",
+        also: &["unique references", "mutable reference", "mutable references"],
+        definition: "A mutable reference, &mut. While it's alive it's the only way to reach the data, so the owner loses R, W and O.",
+        example: Some("let num: &mut u64 = &mut ids[2];  //ids can't even be read until num is done"),
+        introduced: "references_and_borrowing::explain_mutable_references",
+        lesson: "references_and_borrowing",
+        see: &["reference", "permissions"],
+    },
+    Term {
+        name: "lifetime",
+        also: &["lifetimes"],
+        definition: "The stretch of code from where a reference is made to where it's last used. \
+            With control flow it doesn't have to be a whole block.",
+        example: None,
+        introduced: "references_and_borrowing::explain_lifetimes",
+        lesson: "references_and_borrowing",
+        see: &["Flow permission"],
+    },
+    Term {
+        name: "Flow permission",
+        also: &["F permission"],
+        definition: "Whether a reference can flow somewhere else, like out of a function. \
+            When a signature doesn't say which input the output comes from, no input gets it.",
+        example: Some("fn flow(im: &Vec<i32>, confused: &i32) -> &i32  //E0106"),
+        introduced: "references_and_borrowing::explain_data_outliving_references",
+        lesson: "references_and_borrowing",
+        see: &["permissions", "lifetime"],
+    },
+    Term {
+        name: "slice",
+        also: &["slices"],
+        definition: "A reference to part of a collection, like &string[0..5] or &array[1..3].",
+        example: Some("let slice: &str = &string[0..sep_index];"),
+        introduced: "slice::showcase_string_slices",
+        lesson: "slice",
+        see: &["fat pointer"],
+    },
+    Term {
+        name: "fat pointer",
+        also: &["fat pointers"],
+        definition: "A pointer that carries metadata along with the address. A slice carries its length, so it's 8 bytes bigger.",
+        example: None,
+        introduced: "slice::showcase_string_slices",
+        lesson: "slice",
+        see: &["slice"],
+    },
+    Term {
+        name: "dereference coercion",
+        also: &["dereference coercions", "deref coercion"],
+        definition: "Rust turning a reference into another kind of reference by dereferencing it for you, \
+            like passing a &String where a &str is wanted.",
+        example: Some("fn takes_a_slice(s: &str)\ntakes_a_slice(&generic_string);  //the &String becomes a &str"),
+        introduced: "slice::explain_string_literals",
+        lesson: "slice",
+        see: &["dereference", "slice"],
+    },
+    Term {
+        name: "Copy trait",
+        also: &[],
+        definition: "Types that own no heap data, like i32, are copied instead of moved, \
+            so copying one out through a reference is fine.",
+        example: Some("let n_ref: &i32 = &v[0];\n*n_ref  //copies the 0 out, v keeps its own"),
+        introduced: "ownership_case_study::safe_copy",
+        lesson: "ownership_case_study::case4",
+        see: &["move"],
+    },
+];
+
+//By name or any of its other names, ignoring case. Otherwise the terms with the query somewhere in their name
+pub fn find(query: &str) -> Vec<&'static Term> {
+    let query = query.trim().to_lowercase();
+    let names = |t: &&Term| std::iter::once(t.name).chain(t.also.iter().copied()).map(str::to_lowercase).collect::<Vec<_>>();
+    let exact: Vec<&Term> = TERMS.iter().filter(|t| names(t).contains(&query)).collect();
+    if !exact.is_empty() {
+        return exact;
+    }
+    TERMS.iter().filter(|t| names(t).iter().any(|n| n.contains(&query))).collect()
+}
+
+pub fn list(ctx: &mut Context) {
+    for term in &TERMS {
+        say!(ctx, "{:<26} {}", term.name, term.introduced);
+    }
+}
+
+pub fn show(ctx: &mut Context, term: &Term) {
+    say!(ctx, "{}\n", term.name);
+    say!(ctx, "{}", term.definition);
+    if let Some(example) = term.example {
+        say!(ctx);
+        for line in example.lines() {
+            say!(ctx, "    {line}");
+        }
+    }
+
+    say!(ctx, "\nIntroduced in {}:", term.introduced);
+    if let Some(signature) = source::function(term.introduced).and_then(|f| f.lines().next()) {
+        say!(ctx, "    {}", signature.trim());
+    }
+    say!(ctx, "Run it with `ownership run {}`", term.lesson);
+    if !term.see.is_empty() {
+        say!(ctx, "See also: {}", term.see.join(", "));
+    }
+}
+
+//Wraps every glossary term in the line in bold, longest name first wherever two start at the same spot
+pub fn highlight(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut i = 0;
+    while i < line.len() {
+        let starts_word = line[..i].chars().next_back().is_none_or(|c| !is_word(c));
+        let found = starts_word.then(|| term_at(&line[i..])).flatten();
+        match found {
+            Some(len) => {
+                out += &format!("\x1b[1m{}\x1b[0m", &line[i..i + len]);
+                i += len;
+            }
+            None => {
+                let c = line[i..].chars().next().expect("i is always on a char boundary");
+                out.push(c);
+                i += c.len_utf8();
+            }
+        }
+    }
+    out
+}
+
+//How long the term that starts the text is, if one does (and it ends at the end of a word)
+fn term_at(text: &str) -> Option<usize> {
+    TERMS
+        .iter()
+        .flat_map(|t| std::iter::once(t.name).chain(t.also.iter().copied()))
+        .filter(|name| text.get(..name.len()).is_some_and(|s| s.eq_ignore_ascii_case(name)))
+        .filter(|name| text[name.len()..].chars().next().is_none_or(|c| !is_word(c)))
+        .map(str::len)
+        .max()
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bold(term: &str) -> String {
+        format!("\x1b[1m{term}\x1b[0m")
+    }
+
+    #[test]
+    fn terms_only_match_whole_words() {
+        //"borrowed" is a term of its own, so it's bold as a whole, never as "borrow" plus "ed"
+        assert_eq!(highlight("it's borrowed"), format!("it's {}", bold("borrowed")));
+        assert_eq!(highlight("a reborrow"), "a reborrow");
+        assert_eq!(highlight("borrowing_rules"), "borrowing_rules");
+        assert_eq!(highlight("Borrow it."), format!("{} it.", bold("Borrow")));
+    }
+
+    #[test]
+    fn the_longest_term_wins() {
+        assert_eq!(highlight("a mutable reference"), format!("a {}", bold("mutable reference")));
+        assert_eq!(highlight("a reference"), format!("a {}", bold("reference")));
+        assert_eq!(highlight("dereference coercion!"), format!("{}!", bold("dereference coercion")));
+    }
+
+    #[test]
+    fn multi_byte_text_is_left_as_it_is() {
+        assert_eq!(highlight("→ slice ✓"), format!("→ {} ✓", bold("slice")));
+        assert_eq!(highlight("éslice"), "éslice");
+        assert_eq!(highlight("slices…"), format!("{}…", bold("slices")));
+        assert_eq!(highlight("日本語"), "日本語");
+    }
+}
@@ -0,0 +1,47 @@
+use std::collections::VecDeque;
+use std::{fs, io};
+
+//Where lessons get their answers from: the terminal, or answers written down ahead of time
+pub trait Input {
+    //None means there's nothing left to read (closed stdin, ran out of answers...)
+    fn read_line(&mut self) -> Option<String>;
+}
+
+pub struct Terminal;
+
+impl Input for Terminal {
+    fn read_line(&mut self) -> Option<String> {
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line.trim_end_matches(['\n', '\r']).to_string()),
+        }
+    }
+}
+
+//Answers handed out one at a time, in order
+pub struct Scripted {
+    answers: VecDeque<String>,
+}
+
+impl Scripted {
+    pub fn new<I, S>(answers: I) -> Scripted
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Scripted { answers: answers.into_iter().map(Into::into).collect() }
+    }
+
+    //One answer per line, used by `--input <file>`
+    pub fn from_file(path: &str) -> io::Result<Scripted> {
+        let contents = fs::read_to_string(path)?;
+        Ok(Scripted::new(contents.lines()))
+    }
+}
+
+impl Input for Scripted {
+    fn read_line(&mut self) -> Option<String> {
+        self.answers.pop_front()
+    }
+}
@@ -0,0 +1,273 @@
+use std::path::PathBuf;
+use rand::rngs::StdRng;
+use crate::input::Input;
+use crate::glossary;
+use crate::output::Output;
+use crate::{ownership, ownership_case_study, references_and_borrowing, slice, vectors};
+
+//Everything a lesson is allowed to touch while it runs, handed in by whoever runs it
+pub struct Context {
+    pub input: Box<dyn Input>,
+    pub output: Box<dyn Output>,
+    //seeded with --seed so a walkthrough (or a test) comes out the same every time
+    pub rng: StdRng,
+    //compile the "this doesn't compile" examples and show what rustc really says
+    pub diagnostics: bool,
+    //with --dot, memory diagrams are also written here as Graphviz files
+    pub dot: Option<PathBuf>,
+    //how many diagrams have been drawn so far, to number the files
+    pub diagrams: usize,
+    //make glossary terms stand out, for a terminal that can show bold text
+    pub highlight: bool,
+}
+
+impl Context {
+    pub fn new(input: Box<dyn Input>, output: Box<dyn Output>, rng: StdRng) -> Context {
+        Context { input, output, rng, diagnostics: true, dot: None, diagrams: 0, highlight: false }
+    }
+
+    //Use say!(ctx, ...) instead of calling this directly
+    pub fn say(&mut self, line: &str) {
+        match self.highlight {
+            true => self.output.write_line(&glossary::highlight(line)),
+            false => self.output.write_line(line),
+        }
+    }
+
+    //An empty answer if there's nothing left to read, so lessons never panic on a closed stdin
+    pub fn read_line(&mut self) -> String {
+        self.input.read_line().unwrap_or_default()
+    }
+}
+
+//Every lesson the binary can teach implements this, so main doesn't need to know about any of them
+pub trait Lesson {
+    //the name used to pick the lesson, like "ownership" or "ownership::recap"
+    fn name(&self) -> &'static str;
+    fn title(&self) -> &'static str;
+    //the function the lesson starts at, like "ownership::introduce", so its source can be shown
+    fn entry(&self) -> &'static str;
+    //lessons that should be done first, by name
+    fn prerequisites(&self) -> &'static [&'static str];
+    fn run(&self, ctx: &mut Context);
+}
+
+//Most lessons are just a function that starts a chain of explanations
+pub struct FnLesson {
+    name: &'static str,
+    title: &'static str,
+    entry: &'static str,
+    requires: &'static [&'static str],
+    start: fn(&mut Context),
+}
+
+impl Lesson for FnLesson {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn title(&self) -> &'static str {
+        self.title
+    }
+
+    fn entry(&self) -> &'static str {
+        self.entry
+    }
+
+    fn prerequisites(&self) -> &'static [&'static str] {
+        self.requires
+    }
+
+    fn run(&self, ctx: &mut Context) {
+        (self.start)(ctx)
+    }
+}
+
+pub fn registry() -> Vec<Box<dyn Lesson>> {
+    vec![
+        Box::new(FnLesson {
+            name: "ownership",
+            title: "What ownership is and how moves work",
+            entry: "ownership::introduce",
+            requires: &[],
+            start: ownership::introduce,
+        }),
+        Box::new(FnLesson {
+            name: "references_and_borrowing",
+            title: "References, dereferencing, aliasing and permissions",
+            entry: "references_and_borrowing::explain",
+            //explain_aliasing runs the vectors lesson partway through
+            requires: &["ownership", "vectors"],
+            start: references_and_borrowing::explain,
+        }),
+        Box::new(FnLesson {
+            name: "ownership_case_study",
+            title: "Fixing code that Rust rejects",
+            entry: "ownership_case_study::explain",
+            requires: &["references_and_borrowing"],
+            start: ownership_case_study::explain,
+        }),
+        Box::new(FnLesson {
+            name: "ownership_case_study::case1",
+            title: "Case 1: returning a reference to the stack",
+            entry: "ownership_case_study::case1",
+            requires: &["references_and_borrowing"],
+            start: ownership_case_study::case1,
+        }),
+        Box::new(FnLesson {
+            name: "ownership_case_study::case2",
+            title: "Case 2: not enough permissions",
+            entry: "ownership_case_study::case2",
+            requires: &["references_and_borrowing"],
+            start: ownership_case_study::case2,
+        }),
+        Box::new(FnLesson {
+            name: "ownership_case_study::case3",
+            title: "Case 3: aliasing and mutating a data structure",
+            entry: "ownership_case_study::case3",
+            requires: &["references_and_borrowing"],
+            start: ownership_case_study::case3,
+        }),
+        Box::new(FnLesson {
+            name: "ownership_case_study::case4",
+            title: "Case 4: copying vs. moving out of a collection",
+            entry: "ownership_case_study::case4",
+            requires: &["references_and_borrowing"],
+            start: ownership_case_study::case4,
+        }),
+        Box::new(FnLesson {
+            name: "ownership_case_study::case5",
+            title: "Case 5: mutating different tuple fields",
+            entry: "ownership_case_study::case5",
+            requires: &["references_and_borrowing"],
+            start: ownership_case_study::case5,
+        }),
+        Box::new(FnLesson {
+            name: "ownership_case_study::case6",
+            title: "Case 6: mutating different array elements",
+            entry: "ownership_case_study::case6",
+            requires: &["references_and_borrowing"],
+            start: ownership_case_study::case6,
+        }),
+        Box::new(FnLesson {
+            name: "slice",
+            title: "Slices and fat pointers",
+            entry: "slice::explain",
+            requires: &["references_and_borrowing"],
+            start: slice::explain,
+        }),
+        Box::new(FnLesson {
+            name: "vectors",
+            title: "Vectors and how they grow",
+            entry: "vectors::explain",
+            requires: &["ownership"],
+            start: vectors::explain,
+        }),
+        Box::new(FnLesson {
+            name: "ownership::recap",
+            title: "Why ownership exists at all",
+            entry: "ownership::recap",
+            requires: &["references_and_borrowing", "slice"],
+            start: |_| ownership::recap(),
+        }),
+    ]
+}
+
+pub fn find<'a>(lessons: &'a [Box<dyn Lesson>], name: &str) -> Option<&'a dyn Lesson> {
+    lessons.iter().find(|l| l.name() == name).map(|l| l.as_ref())
+}
+
+//The lessons in the order to take them: every lesson after its prerequisites, otherwise in the order
+//they're registered. Prerequisites that don't exist or that go in a circle are an error
+pub fn recommended(mut lessons: Vec<Box<dyn Lesson>>) -> Result<Vec<Box<dyn Lesson>>, String> {
+    for lesson in &lessons {
+        for needed in lesson.prerequisites() {
+            if find(&lessons, needed).is_none() {
+                return Err(format!("{} needs a lesson called '{needed}', but there isn't one", lesson.name()));
+            }
+        }
+    }
+    for lesson in &lessons {
+        if let Some(cycle) = cycle(&lessons, &mut vec![lesson.name()]) {
+            return Err(format!("lesson prerequisites go in a circle: {}", cycle.join(" -> ")));
+        }
+    }
+
+    let mut path: Vec<Box<dyn Lesson>> = Vec::new();
+    while !lessons.is_empty() {
+        let ready = lessons
+            .iter()
+            .position(|l| l.prerequisites().iter().all(|p| path.iter().any(|done| done.name() == *p)))
+            .expect("there's no cycle, so some lesson is always ready");
+        path.push(lessons.remove(ready));
+    }
+    Ok(path)
+}
+
+//Follows prerequisites from the last lesson in `chain`, returning the chain once it comes back around
+fn cycle(lessons: &[Box<dyn Lesson>], chain: &mut Vec<&'static str>) -> Option<Vec<&'static str>> {
+    let last = find(lessons, chain.last()?)?;
+    for needed in last.prerequisites() {
+        if let Some(start) = chain.iter().position(|name| name == needed) {
+            let mut cycle = chain[start..].to_vec();
+            cycle.push(needed);
+            return Some(cycle);
+        }
+        chain.push(needed);
+        if let Some(cycle) = cycle(lessons, chain) {
+            return Some(cycle);
+        }
+        chain.pop();
+    }
+    None
+}
+
+//Every prerequisite of the lesson (and of those, and so on) that isn't done yet, in the order of `lessons`
+pub fn missing<'a>(lessons: &'a [Box<dyn Lesson>], lesson: &dyn Lesson, done: impl Fn(&str) -> bool) -> Vec<&'a dyn Lesson> {
+    let mut needed: Vec<&str> = Vec::new();
+    let mut todo: Vec<&str> = lesson.prerequisites().to_vec();
+    while let Some(name) = todo.pop() {
+        if needed.contains(&name) || done(name) {
+            continue;
+        }
+        needed.push(name);
+        if let Some(prerequisite) = find(lessons, name) {
+            todo.extend(prerequisite.prerequisites());
+        }
+    }
+    lessons.iter().filter(|l| needed.contains(&l.name())).map(|l| l.as_ref()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lesson(name: &'static str, requires: &'static [&'static str]) -> Box<dyn Lesson> {
+        Box::new(FnLesson { name, title: name, entry: name, requires, start: |_| {} })
+    }
+
+    fn names(lessons: &[Box<dyn Lesson>]) -> Vec<&'static str> {
+        lessons.iter().map(|l| l.name()).collect()
+    }
+
+    #[test]
+    fn prerequisites_come_first_and_ties_keep_their_order() {
+        let lessons = vec![lesson("c", &["b"]), lesson("a", &[]), lesson("b", &["a"]), lesson("d", &[])];
+        let path = recommended(lessons).expect("there's no cycle");
+        assert_eq!(names(&path), ["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn a_cycle_is_named_in_full() {
+        let lessons = vec![lesson("x", &[]), lesson("a", &["b"]), lesson("b", &["a"])];
+        let error = recommended(lessons).err().expect("a and b need each other");
+        assert_eq!(error, "lesson prerequisites go in a circle: a -> b -> a");
+    }
+
+    #[test]
+    fn an_unknown_prerequisite_names_who_needs_it() {
+        let lessons = vec![lesson("a", &[]), lesson("x", &["a", "nope"])];
+        let error = recommended(lessons).err().expect("there's no lesson called nope");
+        assert_eq!(error, "x needs a lesson called 'nope', but there isn't one");
+    }
+}
@@ -1,19 +1,302 @@
+mod anki;
+mod cli;
+mod compiles;
+mod diagnostics;
+mod glossary;
+mod input;
+mod lesson;
+mod memory;
+mod output;
+mod paths;
+mod permissions;
+mod progress;
+mod quiz;
+mod search;
+mod source;
+mod toy;
 mod ownership;
 mod references_and_borrowing;
 mod vectors;
 mod ownership_case_study;
 mod slice;
 
-use std::io;
+use std::io::IsTerminal;
+use std::{env, process};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use cli::Command;
+use input::{Input, Scripted};
+use lesson::{Context, Lesson};
+use output::{say, Html, Output, Transcript};
+use progress::Progress;
 
 fn main() {
-    //ownership::introduce();
-    //references_and_borrowing::explain();
-    // ownership_case_study::explain();
-    //slice::explain();;
-    //ownership::recap();
-
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).expect("Expected to get input");
-    println!("{input}");
+    let args: Vec<String> = env::args().skip(1).collect();
+    let cli = match cli::parse(&args) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("error: {e}\n\n{}", cli::USAGE);
+            process::exit(2);
+        }
+    };
+
+    let input: Box<dyn Input> = match &cli.input {
+        Some(path) => match Scripted::from_file(path) {
+            Ok(answers) => Box::new(answers),
+            Err(e) => {
+                eprintln!("error: couldn't read answers from {path}: {e}");
+                process::exit(1);
+            }
+        },
+        None => Box::new(input::Terminal),
+    };
+
+    //A transcript file, or a web page if the file ends in .html
+    let output: Box<dyn Output> = match &cli.output {
+        Some(path) => {
+            let sink: std::io::Result<Box<dyn Output>> = if path.ends_with(".html") {
+                Html::create(path, "Ownership lessons").map(|h| Box::new(h) as Box<dyn Output>)
+            } else {
+                Transcript::create(path).map(|t| Box::new(t) as Box<dyn Output>)
+            };
+            sink.unwrap_or_else(|e| {
+                eprintln!("error: couldn't write to {path}: {e}");
+                process::exit(1);
+            })
+        }
+        None => Box::new(output::Terminal),
+    };
+
+    let rng = match cli.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let mut ctx = Context::new(input, output, rng);
+    ctx.diagnostics = !cli.no_diagnostics;
+    if let Some(dir) = &cli.dot {
+        if let Err(e) = std::fs::create_dir_all(dir) {
+            eprintln!("error: couldn't create {dir}: {e}");
+            process::exit(1);
+        }
+        ctx.dot = Some(dir.into());
+    }
+    //bold only makes sense on a terminal, not in a transcript or piped somewhere
+    let lessons_or_terms = matches!(cli.command, Command::Menu | Command::Run(_) | Command::RunAll | Command::Glossary(_));
+    ctx.highlight = lessons_or_terms && cli.output.is_none() && std::io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none();
+
+    //--learner, or whoever is logged in
+    let learner = cli.learner.clone().or_else(|| env::var("USER").ok()).filter(|n| !n.is_empty() && !n.contains(char::is_whitespace));
+    let mut progress = Progress::load(learner.as_deref().unwrap_or("learner"));
+
+    //menus, lists and run --all all go in the recommended order
+    let lessons = lesson::recommended(lesson::registry()).unwrap_or_else(|e| {
+        eprintln!("error: {e}");
+        process::exit(1);
+    });
+
+    match cli.command {
+        Command::Menu => menu(&mut ctx, &mut progress, &lessons, &quiz::ids()),
+        Command::List => list(&mut ctx, &lessons),
+        Command::Run(name) => run(&mut ctx, &mut progress, find_or_exit(&lessons, &name)),
+        //every lesson, mastered or not: it's for demos and smoke tests, so it can't depend on who's running it
+        Command::RunAll => {
+            for lesson in &lessons {
+                run(&mut ctx, &mut progress, lesson.as_ref());
+            }
+        }
+        Command::Show(name) => show(&mut ctx, find_or_exit(&lessons, &name)),
+        Command::Toy(program) => {
+            let src = toy_source(&mut ctx, program);
+            toy::run(&mut ctx, &src);
+        }
+        Command::ToyCheck(program) => {
+            let src = toy_source(&mut ctx, program);
+            toy::check(&mut ctx, &src);
+        }
+        Command::ToyStep(program) => {
+            let src = toy_source(&mut ctx, program);
+            toy::step(&mut ctx, &src);
+        }
+        Command::ToyList => toy::list(&mut ctx),
+        Command::Quiz(module) => {
+            if let Some(module) = &module {
+                if !quiz::MODULES.contains(&module.as_str()) {
+                    eprintln!("error: there are no questions about '{module}' (try `ownership quiz list`)");
+                    process::exit(1);
+                }
+            }
+            quiz::run(&mut ctx, &mut progress, &quiz::questions(module.as_deref()));
+        }
+        Command::QuizList => quiz::list(&mut ctx),
+        Command::Review => quiz::review(&mut ctx, &mut progress),
+        Command::Compiles(rounds) => compiles::play(&mut ctx, rounds),
+        Command::Glossary(None) => glossary::list(&mut ctx),
+        Command::Glossary(Some(query)) => match glossary::find(&query).as_slice() {
+            [term] => glossary::show(&mut ctx, term),
+            [] => {
+                eprintln!("error: '{query}' isn't in the glossary (try `ownership glossary`)");
+                process::exit(1);
+            }
+            terms => {
+                let names: Vec<&str> = terms.iter().map(|t| t.name).collect();
+                say!(ctx, "'{query}' could be any of: {}", names.join(", "));
+            }
+        },
+        Command::Search(query) => search::run(&mut ctx, &lessons, &query),
+        Command::Anki(path) => match anki::export(&path) {
+            Ok(n) => say!(ctx, "Wrote {n} notes to {path}. Import it in Anki with File > Import."),
+            Err(e) => {
+                eprintln!("error: couldn't write to {path}: {e}");
+                process::exit(1);
+            }
+        },
+        Command::Help => say!(ctx, "{}", cli::USAGE),
+    }
+}
+
+fn menu(ctx: &mut Context, progress: &mut Progress, lessons: &[Box<dyn Lesson>], quiz_ids: &quiz::Ids) {
+    if let Some(last) = progress.last_lesson() {
+        say!(ctx, "Welcome back, {}! Last time you finished {} ({}).\n", progress.learner(), last.lesson, progress::ago(last.at));
+    }
+
+    loop {
+        let next = resume(progress, quiz_ids, lessons);
+        print_menu(ctx, progress, quiz_ids, lessons, next);
+
+        //Running out of input (closed stdin, end of the answers file) is the same as quitting
+        let Some(input) = ctx.input.read_line() else { break };
+
+        let choice = input.trim();
+        if choice == "q" || choice == "quit" {
+            break;
+        }
+
+        match next.filter(|_| choice.is_empty()).or_else(|| pick(lessons, choice)) {
+            Some(lesson) => {
+                prerequisites_first(ctx, progress, lessons, lesson);
+                run(ctx, progress, lesson);
+            }
+            None => say!(ctx, "There's no lesson '{choice}'.\n"),
+        }
+    }
+}
+
+fn print_menu(ctx: &mut Context, progress: &Progress, quiz_ids: &quiz::Ids, lessons: &[Box<dyn Lesson>], next: Option<&dyn Lesson>) {
+    match next {
+        Some(next) => say!(ctx, "Pick a lesson (number or name), press enter to resume with {}, or q to quit:", next.name()),
+        None => say!(ctx, "Pick a lesson (number or name), or q to quit:"),
+    }
+    for (i, lesson) in lessons.iter().enumerate() {
+        let done = if mastered(progress, quiz_ids, lesson.as_ref()) {
+            " (mastered)"
+        } else if progress.lesson(lesson.name()).is_some() {
+            " (done)"
+        } else {
+            ""
+        };
+        say!(ctx, "  {}. {} - {}{done}", i + 1, lesson.name(), lesson.title());
+    }
+}
+
+//Jumping ahead: says which lessons it builds on haven't been done, and offers to run them first
+fn prerequisites_first(ctx: &mut Context, progress: &mut Progress, lessons: &[Box<dyn Lesson>], lesson: &dyn Lesson) {
+    let missing = lesson::missing(lessons, lesson, |name| progress.lesson(name).is_some());
+    if missing.is_empty() {
+        return;
+    }
+    let names: Vec<&str> = missing.iter().map(|l| l.name()).collect();
+    let them = if missing.len() == 1 { "it" } else { "them" };
+    say!(ctx, "{} builds on {}, which you haven't done yet.", lesson.name(), names.join(", "));
+    say!(ctx, "Run {them} first? (y or n, enter for yes)");
+    let answer = ctx.input.read_line().unwrap_or_default();
+    if matches!(answer.trim(), "" | "y" | "yes") {
+        for prerequisite in missing {
+            run(ctx, progress, prerequisite);
+        }
+    }
+}
+
+//Where to pick up: the first lesson after the last one finished that isn't mastered yet,
+//and whose prerequisites are done. Nothing for someone who hasn't finished anything (or has mastered everything)
+fn resume<'a>(progress: &Progress, quiz_ids: &quiz::Ids, lessons: &'a [Box<dyn Lesson>]) -> Option<&'a dyn Lesson> {
+    let last = progress.last_lesson()?;
+    let after = lessons.iter().position(|l| l.name() == last.lesson).map_or(0, |i| i + 1);
+    let ready = |l: &dyn Lesson| lesson::missing(lessons, l, |name| progress.lesson(name).is_some()).is_empty();
+    lessons.iter().cycle().skip(after).take(lessons.len()).map(|l| l.as_ref()).find(|l| !mastered(progress, quiz_ids, *l) && ready(*l))
+}
+
+//Finished, and every quiz question about its module was answered right the last time
+fn mastered(progress: &Progress, quiz_ids: &quiz::Ids, lesson: &dyn Lesson) -> bool {
+    let module = lesson.name().split("::").next().unwrap_or_default();
+    let ids = quiz_ids.get(module).map_or(&[][..], Vec::as_slice);
+    progress.is_mastered(lesson.name(), ids)
+}
+
+//Lessons can be picked by their number in the menu or by name
+fn pick<'a>(lessons: &'a [Box<dyn Lesson>], choice: &str) -> Option<&'a dyn Lesson> {
+    match choice.parse::<usize>() {
+        Ok(n) if n >= 1 => lessons.get(n - 1).map(|l| l.as_ref()),
+        Ok(_) => None,
+        Err(_) => lesson::find(lessons, choice),
+    }
+}
+
+fn find_or_exit<'a>(lessons: &'a [Box<dyn Lesson>], name: &str) -> &'a dyn Lesson {
+    lesson::find(lessons, name).unwrap_or_else(|| {
+        eprintln!("error: there's no lesson '{name}' (try `ownership list`)");
+        process::exit(1);
+    })
+}
+
+fn list(ctx: &mut Context, lessons: &[Box<dyn Lesson>]) {
+    for lesson in lessons {
+        say!(ctx, "{:<30} {}", lesson.name(), lesson.title());
+    }
+}
+
+fn run(ctx: &mut Context, progress: &mut Progress, lesson: &dyn Lesson) {
+    say!(ctx, "\n=== {} ===\n", lesson.name());
+    lesson.run(ctx);
+    say!(ctx);
+    progress.finish_lesson(lesson.name());
+}
+
+fn show(ctx: &mut Context, lesson: &dyn Lesson) {
+    say!(ctx, "{} - {}", lesson.name(), lesson.title());
+    say!(ctx, "starts at {}", lesson.entry());
+    if !lesson.prerequisites().is_empty() {
+        say!(ctx, "builds on {}", lesson.prerequisites().join(", "));
+    }
+    say!(ctx);
+
+    match source::function(lesson.entry()) {
+        Some(src) => say!(ctx, "{src}"),
+        None => say!(ctx, "(couldn't find the source for {})", lesson.entry()),
+    }
+}
+
+//An example's name or a file. Without either, the program is typed in, ending with a line that says "run"
+fn toy_source(ctx: &mut Context, program: Option<String>) -> String {
+    match program {
+        Some(name) => match toy::source(&name) {
+            Some(src) => src.to_string(),
+            None => std::fs::read_to_string(&name).unwrap_or_else(|e| {
+                eprintln!("error: '{name}' isn't an example (try `ownership toy list`) or a file I can read: {e}");
+                process::exit(1);
+            }),
+        },
+        None => {
+            say!(ctx, "Type a program, then a line that says run:");
+            let mut src = String::new();
+            while let Some(line) = ctx.input.read_line() {
+                if line.trim() == "run" {
+                    break;
+                }
+                src.push_str(&line);
+                src.push('\n');
+            }
+            src
+        }
+    }
 }
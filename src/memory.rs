@@ -0,0 +1,275 @@
+use std::fs;
+use crate::lesson::Context;
+use crate::output::{escape, say};
+
+//A picture of memory at one point in a program: stack frames on one side, heap allocations on the other.
+//Lessons build these by hand to show each numbered step, and draw() turns them into ASCII
+#[derive(Clone, Default)]
+pub struct Memory {
+    pub frames: Vec<Frame>,
+    pub heap: Vec<Allocation>,
+}
+
+#[derive(Clone)]
+pub struct Frame {
+    pub function: String,
+    pub slots: Vec<Slot>,
+}
+
+#[derive(Clone)]
+pub struct Slot {
+    pub name: String,
+    pub value: Value,
+    //moved-from variables stay on the stack, they just can't be used anymore
+    pub moved: bool,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum Value {
+    //a pointer that owns a heap allocation, like a String or a Box
+    Owns(usize),
+    //a pointer that doesn't own what it points to
+    Ref { to: Target, mutable: bool },
+    //a number, a bool... anything that lives right there on the stack
+    Plain(String),
+}
+
+//What a reference points at: a variable (or part of one) on the stack, or something on the heap
+#[derive(Clone, PartialEq)]
+pub enum Target {
+    //None when the frame it was in has already returned
+    Slot { frame: Option<usize>, name: String },
+    Heap { address: usize, index: Option<usize> },
+}
+
+#[derive(Clone)]
+pub struct Allocation {
+    pub contents: String,
+    pub freed: bool,
+    //other allocations this one owns, like the Strings inside a Vec<String>
+    pub owns: Vec<usize>,
+}
+
+impl Memory {
+    pub fn new() -> Memory {
+        Memory::default()
+    }
+
+    pub fn push_frame(&mut self, function: &str) {
+        self.frames.push(Frame { function: function.to_string(), slots: Vec::new() });
+    }
+
+    pub fn pop_frame(&mut self) {
+        self.frames.pop();
+    }
+
+    //Puts something on the heap and returns its address (just its index, #0, #1...)
+    pub fn alloc(&mut self, contents: &str) -> usize {
+        self.heap.push(Allocation { contents: contents.to_string(), freed: false, owns: Vec::new() });
+        self.heap.len() - 1
+    }
+
+    pub fn free(&mut self, address: usize) {
+        self.heap[address].freed = true;
+    }
+
+    //Declares a variable in the current (top) frame, or overwrites it if it's already there
+    pub fn bind(&mut self, name: &str, value: Value) {
+        let frame = self.frames.last_mut().expect("bind needs a frame");
+        let slot = Slot { name: name.to_string(), value, moved: false };
+        match frame.slots.iter_mut().find(|s| s.name == name) {
+            Some(existing) => *existing = slot,
+            None => frame.slots.push(slot),
+        }
+    }
+
+    //Moves a variable out of the current frame: the slot stays, but it's marked as moved
+    pub fn take(&mut self, name: &str) -> Value {
+        let frame = self.frames.last_mut().expect("take needs a frame");
+        let slot = frame.slots.iter_mut().find(|s| s.name == name).expect("take needs a variable that exists");
+        slot.moved = true;
+        slot.value.clone()
+    }
+
+    //Every live variable (or allocation) that owns an allocation, like ["stupid"] or ["#0"]
+    fn owners(&self, address: usize) -> Vec<String> {
+        let on_stack = self
+            .frames
+            .iter()
+            .flat_map(|f| &f.slots)
+            .filter(|s| !s.moved && s.value == Value::Owns(address))
+            .map(|s| s.name.clone());
+        let on_heap = (0..self.heap.len())
+            .filter(|&i| !self.heap[i].freed && self.heap[i].owns.contains(&address))
+            .map(|i| format!("#{i}"));
+        on_stack.chain(on_heap).collect()
+    }
+}
+
+//  [3] push_str reallocs: new allocation, old one freed
+//  stack
+//  +-- explain_rust_memory ----+
+//  | no_im_not    (moved out)  |
+//  +-- add_ten ----------------+
+//  | number       --> #1       |
+//  +---------------------------+
+//  heap
+//    #0  "nine"                (freed)
+//    #1  "nine plus ten"       <-- number
+pub fn draw(ctx: &mut Context, step: u32, caption: &str, memory: &Memory) {
+    //Wide enough for the longest variable name plus "(moved out)", or a longer value
+    let names = memory.frames.iter().flat_map(|f| &f.slots).map(|s| s.name.len()).max().unwrap_or(0).max(12);
+    let value = |slot: &Slot| match (&slot.value, slot.moved) {
+        (_, true) => String::from("(moved out)"),
+        (Value::Owns(address), false) => format!("--> #{address}"),
+        (Value::Ref { to, mutable }, false) => {
+            let amp = if *mutable { "&mut " } else { "&" };
+            format!("--> {amp}{}", describe(memory, to))
+        }
+        (Value::Plain(text), false) => format!("= {text}"),
+    };
+    let values = memory.frames.iter().flat_map(|f| &f.slots).map(|s| value(s).len()).max().unwrap_or(0);
+    let width = (names + 16).max(names + values + 4);
+
+    say!(ctx);
+    say!(ctx, "  [{step}] {caption}");
+    say!(ctx, "  stack");
+    for frame in &memory.frames {
+        let title = format!("+-- {} ", frame.function);
+        say!(ctx, "  {title:-<width$}+");
+        for slot in &frame.slots {
+            let line = format!("| {:<names$} {}", slot.name, value(slot));
+            say!(ctx, "  {line:<width$}|");
+        }
+    }
+    say!(ctx, "  +{:-<rule$}+", "", rule = width - 1);
+
+    say!(ctx, "  heap");
+    for (address, allocation) in memory.heap.iter().enumerate() {
+        let owners = memory.owners(address);
+        let note = if allocation.freed {
+            String::from("(freed)")
+        } else if owners.is_empty() {
+            String::from("(no owner!)")
+        } else {
+            format!("<-- {}", owners.join(", "))
+        };
+        say!(ctx, "    #{address:<3}{:<20}  {note}", allocation.contents);
+    }
+
+    export(ctx, step, caption, memory);
+}
+
+//--dot: every diagram also goes to its own file, numbered in the order they were drawn.
+//Nothing happens without --dot
+pub fn export(ctx: &mut Context, step: u32, caption: &str, memory: &Memory) {
+    if let Some(dir) = ctx.dot.clone() {
+        ctx.diagrams += 1;
+        let path = dir.join(format!("{:03}-step{step}.dot", ctx.diagrams));
+        if let Err(e) = fs::write(&path, dot(&format!("[{step}] {caption}"), memory)) {
+            eprintln!("warning: couldn't write {}: {e}", path.display());
+        }
+    }
+}
+
+//"a", "name.0", "#1[2]"... frame names get added when it's not the current frame
+pub fn describe(memory: &Memory, target: &Target) -> String {
+    match target {
+        Target::Slot { frame: Some(f), name } if *f + 1 == memory.frames.len() => name.clone(),
+        //its function returned, taking the variable (and its name) with it
+        Target::Slot { frame: None, .. } => String::from("(gone)"),
+        Target::Slot { frame: Some(frame), name } => match memory.frames.get(*frame) {
+            Some(f) => format!("{}::{name}", f.function),
+            None => format!("{name} (gone)"),
+        },
+        Target::Heap { address, index: None } => format!("#{address}"),
+        Target::Heap { address, index: Some(i) } => format!("#{address}[{i}]"),
+    }
+}
+
+//The same picture as a Graphviz graph, for pasting into docs: each frame is a table with a row per
+//slot, and arrows go from a slot to what it points at. Owning pointers are solid, & is dashed blue,
+//&mut is bold red, and moved-out slots (and freed allocations) are greyed out
+pub fn dot(caption: &str, memory: &Memory) -> String {
+    let mut out = String::from("digraph memory {\n");
+    out += &format!("    label=\"{}\";\n    labelloc=t;\n    rankdir=LR;\n", quote(caption));
+    out += "    node [shape=plaintext, fontname=\"monospace\"];\n";
+    let mut edges = Vec::new();
+
+    out += "    subgraph cluster_stack {\n        label=\"stack\";\n";
+    for (f, frame) in memory.frames.iter().enumerate() {
+        out += &format!("        frame{f} [label=<<table border=\"0\" cellborder=\"1\" cellspacing=\"0\">");
+        out += &format!("<tr><td colspan=\"2\"><b>{}</b></td></tr>", escape(&frame.function));
+        for (s, slot) in frame.slots.iter().enumerate() {
+            let value = match (&slot.value, slot.moved) {
+                (_, true) => String::from("(moved out)"),
+                (Value::Plain(text), false) => escape(text),
+                //nothing left to draw an arrow to
+                (Value::Ref { to, .. }, false) if node(memory, to).is_none() => String::from("(gone)"),
+                (_, false) => String::from("&#8226;"),
+            };
+            let name = escape(&slot.name);
+            out += &match slot.moved {
+                true => format!("<tr><td bgcolor=\"lightgrey\"><font color=\"grey40\">{name}</font></td><td port=\"s{s}\" bgcolor=\"lightgrey\"><font color=\"grey40\">{value}</font></td></tr>"),
+                false => format!("<tr><td>{name}</td><td port=\"s{s}\">{value}</td></tr>"),
+            };
+            if slot.moved {
+                continue;
+            }
+            let from = format!("frame{f}:s{s}:c");
+            match &slot.value {
+                Value::Owns(address) => edges.push(format!("{from} -> heap{address} [tailclip=false];")),
+                Value::Ref { to, mutable } => {
+                    let style = if *mutable { "style=bold, color=red" } else { "style=dashed, color=blue" };
+                    let label = match to {
+                        Target::Heap { index: Some(i), .. } => format!(", label=\"[{i}]\""),
+                        _ => String::new(),
+                    };
+                    if let Some(to) = node(memory, to) {
+                        edges.push(format!("{from} -> {to} [tailclip=false, {style}{label}];"));
+                    }
+                }
+                Value::Plain(_) => {}
+            }
+        }
+        out += "</table>>];\n";
+    }
+    out += "    }\n";
+
+    out += "    subgraph cluster_heap {\n        label=\"heap\";\n";
+    for (address, allocation) in memory.heap.iter().enumerate() {
+        let look = if allocation.freed { ", style=\"filled,dashed\", fillcolor=lightgrey, fontcolor=grey40" } else { "" };
+        let freed = if allocation.freed { " (freed)" } else { "" };
+        let label = quote(&format!("#{address}  {}{freed}", allocation.contents));
+        out += &format!("        heap{address} [shape=box, label=\"{label}\"{look}];\n");
+        for owned in &allocation.owns {
+            edges.push(format!("heap{address} -> heap{owned};"));
+        }
+    }
+    out += "    }\n";
+
+    for edge in edges {
+        out += &format!("    {edge}\n");
+    }
+    out += "}\n";
+    out
+}
+
+//Where an arrow to this target ends: a slot's row, or a heap allocation
+fn node(memory: &Memory, target: &Target) -> Option<String> {
+    match target {
+        Target::Heap { address, .. } => Some(format!("heap{address}")),
+        Target::Slot { frame, name } => {
+            //name.0 and a[1] point into the variable, so the arrow goes to the variable's row
+            let variable = name.split(['.', '[']).next().unwrap_or(name);
+            let frame = (*frame)?;
+            let slot = memory.frames.get(frame)?.slots.iter().position(|s| s.name == variable)?;
+            Some(format!("frame{frame}:s{slot}:w"))
+        }
+    }
+}
+
+//For a "quoted" label
+fn quote(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,110 @@
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+//Where lessons write to. Lessons only ever write whole lines, so that's all a sink needs to handle
+pub trait Output {
+    fn write_line(&mut self, line: &str);
+}
+
+//Lessons write with say!(ctx, ...) exactly like they would with println!(...)
+macro_rules! say {
+    ($ctx:expr) => {
+        $ctx.say("")
+    };
+    ($ctx:expr, $($arg:tt)*) => {
+        $ctx.say(&format!($($arg)*))
+    };
+}
+pub(crate) use say;
+
+pub struct Terminal;
+
+impl Output for Terminal {
+    //Piped into something like `head` that stops reading: that's the end, not a crash
+    fn write_line(&mut self, line: &str) {
+        if let Err(e) = writeln!(io::stdout().lock(), "{line}") {
+            if e.kind() == io::ErrorKind::BrokenPipe {
+                std::process::exit(0);
+            }
+            panic!("couldn't write to stdout: {e}");
+        }
+    }
+}
+
+//Keeps everything in memory. Clones share the same text, so keep one to read what the lesson wrote
+#[derive(Clone, Default)]
+pub struct Buffer {
+    text: Rc<RefCell<String>>,
+}
+
+impl Buffer {
+    pub fn new() -> Buffer {
+        Buffer::default()
+    }
+
+    pub fn contents(&self) -> String {
+        self.text.borrow().clone()
+    }
+}
+
+impl Output for Buffer {
+    fn write_line(&mut self, line: &str) {
+        let mut text = self.text.borrow_mut();
+        text.push_str(line);
+        text.push('\n');
+    }
+}
+
+//A plain text file with everything the lesson said
+pub struct Transcript {
+    file: File,
+}
+
+impl Transcript {
+    pub fn create(path: &str) -> io::Result<Transcript> {
+        Ok(Transcript { file: File::create(path)? })
+    }
+}
+
+impl Output for Transcript {
+    fn write_line(&mut self, line: &str) {
+        //A lesson can't do anything useful about a full disk, so just stop writing
+        let _ = writeln!(self.file, "{line}");
+    }
+}
+
+//A standalone web page. The closing tags get written when the page is dropped
+pub struct Html {
+    file: File,
+}
+
+impl Html {
+    pub fn create(path: &str, title: &str) -> io::Result<Html> {
+        let mut file = File::create(path)?;
+        writeln!(file, "<!DOCTYPE html>")?;
+        writeln!(file, "<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>", escape(title))?;
+        writeln!(file, "<body>\n<pre>")?;
+        Ok(Html { file })
+    }
+}
+
+impl Output for Html {
+    fn write_line(&mut self, line: &str) {
+        let _ = writeln!(self.file, "{}", escape(line));
+    }
+}
+
+impl Drop for Html {
+    fn drop(&mut self) {
+        let _ = writeln!(self.file, "</pre>\n</body>\n</html>");
+    }
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -1,15 +1,20 @@
 #![allow(dead_code)]
 
-pub fn introduce() {
-    println!("Ownership is a method to ensure the safety of Rust code.");
+use crate::diagnostics;
+use crate::lesson::Context;
+use crate::memory::{self, Memory, Value};
+use crate::output::say;
+
+pub fn introduce(ctx: &mut Context) {
+    say!(ctx, "Ownership is a method to ensure the safety of Rust code.");
     
     //if a program is safe, all of its behaviors are defined
 
     //this program is safe, but if these two lines were swapped it wouldn't be
     let msg = "Hello, World!";
-    this_is_safe(msg);
+    this_is_safe(ctx, msg);
 
-    explain_ownership();
+    explain_ownership(ctx);
 }
 
 
@@ -21,42 +26,65 @@ pub fn introduce() {
     //check for undefined behavior at compile-time, instead of runtime (so you don't have to look for it)
 
 //see explanation slides for more
-fn this_is_safe(msg: &str) {
+fn this_is_safe(ctx: &mut Context, msg: &str) {
     if msg.len() < 9 {
-        println!("Here's a short message: {msg}");
+        say!(ctx, "Here's a short message: {msg}");
     } else {
-        println!("Here's a long message: {msg}");
+        say!(ctx, "Here's a long message: {msg}");
     }
 }
 
 
-fn explain_ownership() {
-    println!("So how does ownership help us prevent these 'undefined behaviors?'");
+fn explain_ownership(ctx: &mut Context) {
+    say!(ctx, "So how does ownership help us prevent these 'undefined behaviors?'");
 
     //Rust provides a new way of thinking about memory, 
     //and ownership allows safe memory use within that way of thinking.
 
-    explain_rust_memory();
+    explain_rust_memory(ctx);
 }
 
 
-fn explain_rust_memory() {
-    println!("Check out the explanation document to find out how Rust handles memory!");
+fn explain_rust_memory(ctx: &mut Context) {
+    say!(ctx, "Here's how Rust handles memory, one step at a time:");
+    let mut mem = Memory::new();
+    mem.push_frame("explain_rust_memory");
 
     //1: String "nine" has owner no_im_not
     let no_im_not = String::from("nine");
+    let nine = mem.alloc("\"nine\"");
+    mem.bind("no_im_not", Value::Owns(nine));
+    memory::draw(ctx, 1, "no_im_not owns the String \"nine\" on the heap", &mem);
+
+    //(steps 2 and 3 happen inside add_ten)
+    let moved = mem.take("no_im_not");
+    mem.push_frame("add_ten");
+    mem.bind("number", moved);
+    memory::draw(ctx, 2, "no_im_not moved into add_ten's number", &mem);
+
+    let nine_plus_ten = mem.alloc("\"nine plus ten\"");
+    mem.free(nine);
+    mem.bind("number", Value::Owns(nine_plus_ten));
+    memory::draw(ctx, 3, "push_str reallocs: new allocation, old one freed", &mem);
 
     //4: number's ownership has been transferred to stupid
     let stupid = add_ten(no_im_not);
-    println!("What's {stupid}");
+    let returned = mem.take("number");
+    mem.pop_frame();
+    mem.bind("stupid", returned);
+    memory::draw(ctx, 4, "add_ten returned number, so stupid owns it now", &mem);
+    say!(ctx);
+    say!(ctx, "What's {stupid}");
 
     //This is an error - remember no_im_not has been dealloc'
     //println!("{no_im_not}");
+    say!(ctx, "Using no_im_not after it moved into add_ten doesn't compile:");
+    diagnostics::show(ctx, "use_after_move");
     //It's okay that it points to nothing, but we cannot use it no matter what
 
     //You CANNOT USE variables that have their OWNERSHIP MOVED
 
-    make_it_safe();
+    make_it_safe(ctx);
 }
 
 
@@ -69,16 +97,33 @@ fn add_ten(mut number: String) -> String {
 
 
 //However, we can use cloning to make the program safe
-fn make_it_safe() {
+fn make_it_safe(ctx: &mut Context) {
+    let mut mem = Memory::new();
+    mem.push_frame("make_it_safe");
+
     let quick_maths = String::from("nine");
     let quick_maths_clone = quick_maths.clone();
+    let original = mem.alloc("\"nine\"");
+    let clone = mem.alloc("\"nine\"");
+    mem.bind("quick_maths", Value::Owns(original));
+    mem.bind("quick_maths_clone", Value::Owns(clone));
+    memory::draw(ctx, 1, "clone() made a second, separate String", &mem);
+
     let im_smart = add_ten(quick_maths_clone);
+    mem.take("quick_maths_clone");
+    let nine_plus_ten = mem.alloc("\"nine plus ten\"");
+    mem.free(clone);
+    mem.bind("im_smart", Value::Owns(nine_plus_ten));
+    memory::draw(ctx, 2, "only the clone moved into add_ten, quick_maths still owns its String", &mem);
+    say!(ctx);
 
-    println!("What's {im_smart}?");
-    println!("It's {quick_maths}teen.");
+    say!(ctx, "What's {im_smart}?");
+    say!(ctx, "It's {quick_maths}teen.");
 
     //The clone can't be referenced now, though.
     //println!("{quick_maths_clone}");
+    say!(ctx, "But the clone moved into add_ten, so it's gone too:");
+    diagnostics::show(ctx, "use_of_moved_clone");
 
     //END
 } 
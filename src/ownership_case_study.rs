@@ -1,28 +1,35 @@
 #![allow(unused)]
 #![allow(dead_code)]
+#![allow(clippy::let_and_return, clippy::ptr_arg, clippy::useless_vec)]
 
+use crate::diagnostics;
+use crate::lesson::Context;
+use crate::output::say;
 
-pub fn explain() {
-    println!("How should you fix code that Rust rejects?");
+
+pub fn explain(ctx: &mut Context) {
+    say!(ctx, "How should you fix code that Rust rejects?");
 
     //Get ready to see a lot of code that doesn't compile.
 
     //Fixing unsafe programs
-    case1();
-    case2();
-    case3();
-    case4();
+    case1(ctx);
+    case2(ctx);
+    case3(ctx);
+    case4(ctx);
 
     //Fixing safe programs
-    case5();
-    case6();
+    case5(ctx);
+    case6(ctx);
 }
 
 
 
 //Case 1: Returning a reference from the heap to the stack
-fn case1() {
+pub fn case1(ctx: &mut Context) {
     return_a_string();
+    say!(ctx, "Returning a reference to s doesn't compile:");
+    diagnostics::show(ctx, "return_a_string");
 }
 
 //Why is this unsafe? Has to do with lifetimes.
@@ -58,12 +65,14 @@ fn solution1_4(output: &mut String) {
 
 
 //Case 2: Not enough permissions
-fn case2() {
+pub fn case2(ctx: &mut Context) {
     let not_funny = vec![String::from("Johnson")];
     let semi_funny = &not_funny[0];
     //let doubly_not_funny = stringify_name_with_title(&not_funny);
+    say!(ctx, "stringify_name_with_title doesn't compile:");
+    diagnostics::show(ctx, "stringify_name_with_title");
     let doubly_not_funny = solution2_1(&not_funny);
-    println!("{semi_funny}, {}, {doubly_not_funny}", not_funny[0]);
+    say!(ctx, "{semi_funny}, {}, {doubly_not_funny}", not_funny[0]);
 }
 
 //Unsafe because name is immutable and it has to be because pushing could invalidate other references
@@ -107,10 +116,12 @@ fn solution2_2(name: &Vec<String>) -> String {
 
 
 //Case 3: Aliasing and mutating a data structure
-fn case3() {
+pub fn case3(ctx: &mut Context) {
     let mut dst = vec![String::from("like"), String::from("a"), String::from("good"), String::from("neighbor")];
     let src = [String::from("State Farm")];
     add_big_strings(&mut dst, &src);
+    say!(ctx, "Pushing into dst while largest is alive doesn't compile:");
+    diagnostics::show(ctx, "add_big_strings");
 }
 
 //This uses iterators and closures we don't know em just ignore em for now
@@ -162,8 +173,12 @@ fn solution3(dst: &mut Vec<String>, src: &[String]) {
 
 //Case 4: Copying vs. Moving out of a collection
 //A common confusion for Rust learners happens when copying data out of a collection
-fn case4() {
-    println!("{}", safe_copy());
+pub fn case4(ctx: &mut Context) {
+    say!(ctx, "{}", safe_copy());
+    say!(ctx, "Copying a String out through a reference doesn't compile:");
+    diagnostics::show(ctx, "unsafe_copy");
+    say!(ctx, "Neither does returning one:");
+    diagnostics::show(ctx, "return_through_reference");
 }
 
 fn safe_copy() -> i32 {
@@ -191,10 +206,10 @@ fn unsafe_copy() /*-> String*/ {
 
 //So how do you get access to an element?
 //Use immutable reference and don't take ownership
-fn solution4_1() -> String {
+fn solution4_1(ctx: &mut Context) -> String {
     let v = vec![String::from("Hello world")];
     let v_ref = &v[0];
-    println!("{}", v_ref);
+    say!(ctx, "{}", v_ref);
     //We can't actually return this, return requires O perms
     // *v_ref
 
@@ -215,11 +230,13 @@ fn solution4_3() -> String {
 
 //Mutating different tuple fields
 //Note that cases 5 and 6 are actually safe, but sometimes Rust will reject a safe program
-fn case5() {
-    solution5_1()
+pub fn case5(ctx: &mut Context) {
+    solution5_1(ctx);
+    say!(ctx, "Going through get_first is safe too, but rustc rejects it:");
+    diagnostics::show(ctx, "get_first");
 }
 
-fn solution5_1() {
+fn solution5_1(ctx: &mut Context) {
     let mut name = (
         String::from("Ferris"),
         String::from("Rustacean")
@@ -227,7 +244,7 @@ fn solution5_1() {
 
     let first = &name.0;
     name.1.push_str(", Esq");
-    println!("{first} {}", name.1);
+    say!(ctx, "{first} {}", name.1);
 }
 
 fn get_first(name: &(String, String)) -> &String {
@@ -254,15 +271,17 @@ fn safe_but_rejected_combine_names() {
 
 //Case 6: Mutating different array elements
 //A similar kind of problem arises when we borrow elements of an array
-fn case6() {
+pub fn case6(ctx: &mut Context) {
     let mut a = [0, 1, 2, 3];
     let x = &mut a[0];
     *x += 1;
-    println!("{a:?}");
+    say!(ctx, "{a:?}");
 
     //The rust borrow checker doesn't have a different path for every index of a, it uses a[_] (every index)
     //sometimes it can't figure out what an index is (like when an index is found by a complex function)
-    safe_but_rejected_array_read_write()
+    safe_but_rejected_array_read_write();
+    say!(ctx, "Using a[0] and a[1] at the same time is safe, but rustc rejects it:");
+    diagnostics::show(ctx, "array_read_write");
 }
 
 fn safe_but_rejected_array_read_write() {
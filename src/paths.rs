@@ -0,0 +1,22 @@
+use std::env;
+use std::path::PathBuf;
+
+//Where things that can be thrown away and rebuilt go, like compiler output.
+//Follows XDG: $XDG_CACHE_HOME, then ~/.cache, then the temp dir if there's no home at all
+pub fn cache_dir() -> PathBuf {
+    let base = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .unwrap_or_else(env::temp_dir);
+    base.join("ownership")
+}
+
+//Where things worth keeping go, like a learner's progress.
+//Follows XDG too: $XDG_DATA_HOME, then ~/.local/share, then the temp dir
+pub fn data_dir() -> PathBuf {
+    let base = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))
+        .unwrap_or_else(env::temp_dir);
+    base.join("ownership")
+}
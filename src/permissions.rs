@@ -0,0 +1,248 @@
+use crate::lesson::Context;
+use crate::output::say;
+
+//The permissions a path (like ids, ids[_] or *num) can have:
+//Read, Write, Own, and Flow (allowed to flow in or out of a function through a reference)
+#[derive(Clone, Copy, PartialEq, Default)]
+pub struct Perms {
+    pub r: bool,
+    pub w: bool,
+    pub o: bool,
+    pub f: bool,
+}
+
+impl Perms {
+    //"RWO", "R", or "-" for nothing
+    pub fn letters(&self) -> String {
+        let letters: String = [(self.r, 'R'), (self.w, 'W'), (self.o, 'O'), (self.f, 'F')]
+            .iter()
+            .filter(|(has, _)| *has)
+            .map(|(_, c)| *c)
+            .collect();
+        if letters.is_empty() { String::from("-") } else { letters }
+    }
+
+    //What's in self but not in other
+    pub(crate) fn minus(&self, other: &Perms) -> Perms {
+        Perms { r: self.r && !other.r, w: self.w && !other.w, o: self.o && !other.o, f: self.f && !other.f }
+    }
+
+    fn both(&self, other: &Perms) -> Perms {
+        Perms { r: self.r && other.r, w: self.w && other.w, o: self.o && other.o, f: self.f && other.f }
+    }
+
+    fn union(&self, other: &Perms) -> Perms {
+        Perms { r: self.r || other.r, w: self.w || other.w, o: self.o || other.o, f: self.f || other.f }
+    }
+
+    fn is_empty(&self) -> bool {
+        *self == Perms::default()
+    }
+}
+
+//What happens to permissions on one line of a snippet
+pub enum Op {
+    //let [mut] name = <something that owns its data>. indexed: it's a Vec/array, so name[_] is a path too
+    Own { name: &'static str, mutable: bool, indexed: bool },
+    //a reference parameter of the function. flows: whether it's allowed to flow out through the return value
+    Param { name: &'static str, mutable: bool, flows: bool },
+    //let [mut] name = &place or &mut place
+    Borrow { name: &'static str, place: &'static str, mutable: bool, binding_mut: bool },
+    //a reference isn't used anymore, so it dies and hands its permissions back
+    Die(&'static str),
+}
+
+pub struct Line {
+    pub code: &'static str,
+    pub ops: Vec<Op>,
+}
+
+//A reference holding onto the permissions it took away from other paths
+struct Loan {
+    holder: String,
+    taken: Vec<(String, Perms)>,
+    //for reborrows like &*ref_1: the reference this one was borrowed through.
+    //ref_1's own loan can't be paid back while this one is still around
+    through: Option<String>,
+}
+
+#[derive(Default)]
+pub struct Tracker {
+    //in the order paths were introduced, so tables read top to bottom
+    paths: Vec<(String, Perms)>,
+    loans: Vec<Loan>,
+    //references that aren't used anymore
+    dead: Vec<String>,
+}
+
+impl Tracker {
+    pub fn new() -> Tracker {
+        Tracker::default()
+    }
+
+    pub fn get(&self, path: &str) -> Perms {
+        self.paths.iter().find(|(p, _)| p == path).map(|(_, perms)| *perms).unwrap_or_default()
+    }
+
+    //Every path and its permissions, like [("ids", RWO), ("ids[_]", RW)]
+    pub fn paths(&self) -> &[(String, Perms)] {
+        &self.paths
+    }
+
+    fn set(&mut self, path: &str, perms: Perms) {
+        match self.paths.iter_mut().find(|(p, _)| p == path) {
+            Some((_, existing)) => *existing = perms,
+            None => self.paths.push((path.to_string(), perms)),
+        }
+    }
+
+    fn remove(&mut self, path: &str) {
+        self.paths.retain(|(p, _)| p != path);
+    }
+
+    pub fn apply(&mut self, op: &Op) {
+        match *op {
+            Op::Own { name, mutable, indexed } => {
+                self.set(name, Perms { r: true, w: mutable, o: true, f: false });
+                if indexed {
+                    self.set(&format!("{name}[_]"), Perms { r: true, w: mutable, o: false, f: false });
+                }
+            }
+            Op::Param { name, mutable, flows } => {
+                self.set(name, Perms { r: true, w: false, o: true, f: false });
+                self.set(&format!("*{name}"), Perms { r: true, w: mutable, o: false, f: flows });
+            }
+            Op::Borrow { name, place, mutable, binding_mut } => self.borrow(name, place, mutable, binding_mut),
+            Op::Die(name) => self.kill(name),
+        }
+    }
+
+    //A shared borrow takes away W and O from the borrowed path (and the paths around it),
+    //a mutable borrow takes away everything
+    fn borrow(&mut self, name: &str, place: &str, mutable: bool, binding_mut: bool) {
+        let lost = Perms { r: mutable, w: true, o: true, f: false };
+        let place = collapse_indexes(place);
+
+        let mut taken = Vec::new();
+        for (path, perms) in self.paths.iter_mut() {
+            if lends_to(path, &place) || lends_to(&place, path) {
+                let removed = lost.both(perms);
+                if !removed.is_empty() {
+                    *perms = perms.minus(&removed);
+                    taken.push((path.clone(), removed));
+                }
+            }
+        }
+        let through = place.strip_prefix('*').map(|p| p.split(['[', '.']).next().unwrap_or(p).to_string());
+        self.loans.push(Loan { holder: name.to_string(), taken, through });
+
+        self.set(name, Perms { r: true, w: binding_mut, o: true, f: false });
+        self.set(&format!("*{name}"), Perms { r: true, w: mutable, o: false, f: false });
+    }
+
+    //The reference and everything reachable through it go away.
+    //Its loans get paid back once nothing borrowed through it is still alive
+    fn kill(&mut self, name: &str) {
+        self.remove(name);
+        self.remove(&format!("*{name}"));
+        self.dead.push(name.to_string());
+
+        while let Some(i) = self.loans.iter().position(|loan| self.can_pay_back(loan)) {
+            let loan = self.loans.remove(i);
+            for (path, perms) in loan.taken {
+                //paths of references that already died don't come back
+                if self.paths.iter().any(|(p, _)| *p == path) {
+                    let restored = self.get(&path).union(&perms);
+                    self.set(&path, restored);
+                }
+            }
+        }
+    }
+
+    fn can_pay_back(&self, loan: &Loan) -> bool {
+        self.dead.contains(&loan.holder) && !self.loans.iter().any(|other| other.through.as_ref() == Some(&loan.holder))
+    }
+}
+
+//ids[2] -> ids[_]: the borrow checker doesn't know which element an index means
+fn collapse_indexes(place: &str) -> String {
+    let mut out = String::new();
+    let mut in_index = false;
+    for c in place.chars() {
+        match c {
+            '[' => {
+                in_index = true;
+                out.push_str("[_");
+            }
+            ']' => {
+                in_index = false;
+                out.push(']');
+            }
+            _ if in_index => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+//Whether borrowing `place` affects `path`: `path` is `place` or a prefix of it.
+//ids is a prefix of ids[_], num of *num, name of name.0
+fn lends_to(path: &str, place: &str) -> bool {
+    if path == place {
+        return true;
+    }
+    let inner = place.strip_prefix('*').unwrap_or(place);
+    if inner != place && lends_to(path, inner) {
+        return true;
+    }
+    place.strip_prefix(path).is_some_and(|rest| rest.starts_with(['[', '.']))
+}
+
+//How a path changed on one line, like "ids -R -W -O" or "*num +R +W"
+fn change(path: &str, before: Perms, after: Perms) -> Option<String> {
+    let gained = after.minus(&before);
+    let lost = before.minus(&after);
+    if gained.is_empty() && lost.is_empty() {
+        return None;
+    }
+
+    let mut out = String::from(path);
+    for c in lost.letters().chars().filter(|&c| c != '-') {
+        out.push_str(&format!(" -{c}"));
+    }
+    for c in gained.letters().chars().filter(|&c| c != '-') {
+        out.push_str(&format!(" +{c}"));
+    }
+    Some(out)
+}
+
+//Prints, for every line of the snippet, which paths gained or lost which permissions
+//
+//  let num: &mut u64 = &mut ids[2];      ids -R -W -O, ids[_] -R -W, num +R +O, *num +R +W
+pub fn show(ctx: &mut Context, lines: &[Line]) {
+    let width = lines.iter().map(|l| l.code.len()).max().unwrap_or(0);
+    let mut tracker = Tracker::new();
+
+    for line in lines {
+        let before = tracker.paths().to_vec();
+        for op in &line.ops {
+            tracker.apply(op);
+        }
+
+        let mut changes = Vec::new();
+        for (path, perms) in tracker.paths() {
+            let was = before.iter().find(|(p, _)| p == path).map(|(_, p)| *p).unwrap_or_default();
+            changes.extend(change(path, was, *perms));
+        }
+        for (path, was) in &before {
+            if !tracker.paths().iter().any(|(p, _)| p == path) {
+                changes.extend(change(path, *was, Perms::default()));
+            }
+        }
+
+        say!(ctx, "    {:<width$}    {}", line.code, changes.join(", "));
+    }
+
+    let state: Vec<String> = tracker.paths().iter().map(|(p, perms)| format!("{p}: {}", perms.letters())).collect();
+    say!(ctx, "    {:<width$}    (now {})", "", state.join(", "));
+}
@@ -0,0 +1,278 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::paths;
+
+//What every learner has done so far, kept between runs in <data dir>/progress.txt.
+//The first line is the schema version, then one tab-separated record per line:
+//    ownership progress v2
+//    lesson  <learner>  <lesson>  <times finished>  <last finished>
+//    answer  <learner>  <question id>  <times asked>  <times right>  <last answer right>  <last asked>
+//            <easiness x100>  <repetitions>  <interval in days>  <due>
+//Times are seconds since the Unix epoch. The last four are the question's SM-2 review schedule
+
+const VERSION: u32 = 2;
+const DAY: u64 = 24 * 60 * 60;
+//nothing waits longer than a year to come back, however easy it's been
+const MAX_INTERVAL: u32 = 365;
+const HEADER: &str = "ownership progress v";
+
+pub struct Progress {
+    //None when the file couldn't be read, so a broken file never gets overwritten
+    path: Option<PathBuf>,
+    learner: String,
+    lessons: Vec<LessonRecord>,
+    answers: Vec<AnswerRecord>,
+}
+
+pub struct LessonRecord {
+    pub learner: String,
+    pub lesson: String,
+    pub times: u32,
+    pub at: u64,
+}
+
+pub struct AnswerRecord {
+    pub learner: String,
+    pub question: String,
+    pub asked: u32,
+    pub right: u32,
+    pub last_right: bool,
+    pub at: u64,
+    //SM-2: how easy the question has been so far (2.5 to start, never below 1.3), in hundredths
+    pub easiness: u32,
+    //right answers in a row
+    pub repetitions: u32,
+    pub interval: u32,
+    pub due: u64,
+}
+
+impl AnswerRecord {
+    //SM-2, with a right answer graded 4 and a wrong one 2. A wrong answer starts the question over,
+    //due again right away; right ones wait 1 day, then 6, then longer by the easiness each time (up to a year)
+    fn schedule(&mut self, right: bool, now: u64) {
+        let grade: i64 = if right { 4 } else { 2 };
+        let miss = 5 - grade;
+        let easiness = self.easiness as i64 + (10 - miss * (8 + miss * 2));
+        self.easiness = easiness.max(130) as u32;
+
+        if right {
+            self.repetitions += 1;
+            self.interval = match self.repetitions {
+                1 => 1,
+                2 => 6,
+                _ => (self.interval as u64 * self.easiness as u64).div_ceil(100).min(MAX_INTERVAL as u64) as u32,
+            };
+        } else {
+            self.repetitions = 0;
+            self.interval = 0;
+        }
+        self.due = now + self.interval as u64 * DAY;
+    }
+}
+
+impl Progress {
+    pub fn load(learner: &str) -> Progress {
+        let path = paths::data_dir().join("progress.txt");
+        let mut progress = Progress { path: None, learner: learner.to_string(), lessons: Vec::new(), answers: Vec::new() };
+
+        match fs::read_to_string(&path) {
+            Ok(text) => match progress.parse(&text) {
+                //an older file is upgraded on disk right away
+                Ok(version) => {
+                    progress.path = Some(path);
+                    if version < VERSION {
+                        progress.save();
+                    }
+                }
+                Err(e) => eprintln!("warning: {} can't be read ({e}), so progress won't be saved this time", path.display()),
+            },
+            Err(_) => progress.path = Some(path),
+        }
+        progress
+    }
+
+    pub fn learner(&self) -> &str {
+        &self.learner
+    }
+
+    //Returns the version the file was written as
+    fn parse(&mut self, text: &str) -> Result<u32, String> {
+        let mut lines = text.lines();
+        let header = lines.next().unwrap_or_default();
+        let version: u32 = header.strip_prefix(HEADER).and_then(|v| v.parse().ok()).ok_or("it has no version line")?;
+        let records: Vec<Vec<String>> = lines.filter(|l| !l.is_empty()).map(|l| l.split('\t').map(String::from).collect()).collect();
+        let records = migrate(version, records)?;
+
+        for fields in records {
+            let number = |i: usize| fields.get(i).and_then(|f| f.parse::<u64>().ok()).ok_or(format!("bad record: {}", fields.join(" ")));
+            match fields[0].as_str() {
+                "lesson" if fields.len() == 5 => self.lessons.push(LessonRecord {
+                    learner: fields[1].to_string(),
+                    lesson: fields[2].to_string(),
+                    times: number(3)? as u32,
+                    at: number(4)?,
+                }),
+                "answer" if fields.len() == 11 => self.answers.push(AnswerRecord {
+                    learner: fields[1].to_string(),
+                    question: fields[2].to_string(),
+                    asked: number(3)? as u32,
+                    right: number(4)? as u32,
+                    last_right: number(5)? == 1,
+                    at: number(6)?,
+                    easiness: number(7)? as u32,
+                    repetitions: number(8)? as u32,
+                    interval: number(9)? as u32,
+                    due: number(10)?,
+                }),
+                _ => return Err(format!("bad record: {}", fields.join(" "))),
+            }
+        }
+        Ok(version)
+    }
+
+    //Written again after every change, so quitting halfway through loses nothing
+    fn save(&self) {
+        let Some(path) = &self.path else { return };
+        let mut text = format!("{HEADER}{VERSION}\n");
+        for l in &self.lessons {
+            text += &format!("lesson\t{}\t{}\t{}\t{}\n", l.learner, l.lesson, l.times, l.at);
+        }
+        for a in &self.answers {
+            text += &format!(
+                "answer\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                a.learner, a.question, a.asked, a.right, a.last_right as u8, a.at, a.easiness, a.repetitions, a.interval, a.due
+            );
+        }
+
+        //write it next to the real file first, so a crash halfway through can't leave half a file
+        let temp = path.with_extension("txt.tmp");
+        let saved = path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| fs::write(&temp, text)).and_then(|_| fs::rename(&temp, path));
+        if let Err(e) = saved {
+            eprintln!("warning: couldn't save progress to {}: {e}", path.display());
+        }
+    }
+
+    pub fn finish_lesson(&mut self, lesson: &str) {
+        let now = now();
+        match self.lessons.iter_mut().find(|l| l.learner == self.learner && l.lesson == lesson) {
+            Some(record) => {
+                record.times += 1;
+                record.at = now;
+            }
+            None => self.lessons.push(LessonRecord { learner: self.learner.clone(), lesson: lesson.to_string(), times: 1, at: now }),
+        }
+        self.save();
+    }
+
+    pub fn answer(&mut self, question: &str, right: bool) {
+        let now = now();
+        match self.answers.iter_mut().find(|a| a.learner == self.learner && a.question == question) {
+            Some(record) => {
+                record.asked += 1;
+                record.right += right as u32;
+                record.last_right = right;
+                record.at = now;
+                record.schedule(right, now);
+            }
+            None => {
+                let mut record = AnswerRecord {
+                    learner: self.learner.clone(),
+                    question: question.to_string(),
+                    asked: 1,
+                    right: right as u32,
+                    last_right: right,
+                    at: now,
+                    easiness: 250,
+                    repetitions: 0,
+                    interval: 0,
+                    due: now,
+                };
+                record.schedule(right, now);
+                self.answers.push(record);
+            }
+        }
+        self.save();
+    }
+
+    //Questions this learner has seen that are due for review, the longest overdue first
+    pub fn due(&self, now: u64) -> Vec<&AnswerRecord> {
+        let mut due: Vec<&AnswerRecord> = self.answers.iter().filter(|a| a.learner == self.learner && a.due <= now).collect();
+        due.sort_by_key(|a| a.due);
+        due
+    }
+
+    //When the next question comes up for review
+    pub fn next_due(&self) -> Option<u64> {
+        self.answers.iter().filter(|a| a.learner == self.learner).map(|a| a.due).min()
+    }
+
+    pub fn lesson(&self, lesson: &str) -> Option<&LessonRecord> {
+        self.lessons.iter().find(|l| l.learner == self.learner && l.lesson == lesson)
+    }
+
+    pub fn answered(&self, question: &str) -> Option<&AnswerRecord> {
+        self.answers.iter().find(|a| a.learner == self.learner && a.question == question)
+    }
+
+    //The lesson this learner finished most recently
+    pub fn last_lesson(&self) -> Option<&LessonRecord> {
+        self.lessons.iter().filter(|l| l.learner == self.learner).max_by_key(|l| l.at)
+    }
+
+    //Finished, and the last answer to every question about it was right
+    pub fn is_mastered(&self, lesson: &str, questions: &[String]) -> bool {
+        self.lesson(lesson).is_some() && questions.iter().all(|q| self.answered(q).is_some_and(|a| a.last_right))
+    }
+}
+
+//Brings records written by an older version up to date, one version at a time
+fn migrate(version: u32, records: Vec<Vec<String>>) -> Result<Vec<Vec<String>>, String> {
+    match version {
+        VERSION => Ok(records),
+        //v2 added the review schedule. Questions last answered right count as learned once,
+        //due a day after that answer, and the rest are due right away
+        1 => {
+            let upgraded = records
+                .into_iter()
+                .map(|mut fields| {
+                    if fields[0] == "answer" && fields.len() == 7 {
+                        let right = fields[5] == "1";
+                        let at: u64 = fields[6].parse().unwrap_or(0);
+                        let (repetitions, interval) = if right { (1, 1) } else { (0, 0) };
+                        fields.extend([250, repetitions, interval, at + interval * DAY].map(|n| n.to_string()));
+                    }
+                    fields
+                })
+                .collect();
+            migrate(2, upgraded)
+        }
+        v if v > VERSION => Err(format!("it's version {v}, from a newer version of this program")),
+        v => Err(format!("there's no way to upgrade version {v}")),
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+//"today", "yesterday", "3 days ago"
+pub fn ago(at: u64) -> String {
+    match now().saturating_sub(at) / DAY {
+        0 => String::from("today"),
+        1 => String::from("yesterday"),
+        days => format!("{days} days ago"),
+    }
+}
+
+//"now", "tomorrow", "in 6 days"
+pub fn until(at: u64) -> String {
+    let now = now();
+    if at <= now {
+        return String::from("now");
+    }
+    match (at - now).div_ceil(DAY) {
+        1 => String::from("tomorrow"),
+        days => format!("in {days} days"),
+    }
+}
@@ -0,0 +1,223 @@
+mod bank;
+mod predict;
+
+use std::collections::HashMap;
+use crate::lesson::Context;
+use crate::output::say;
+use crate::progress::{self, Progress};
+
+//Questions about each lesson module, asked one at a time with feedback right after every answer
+
+pub use bank::MODULES;
+
+pub struct Question {
+    //"ownership::moved_value": which module it's about, and a name for it
+    pub id: String,
+    pub prompt: String,
+    //shown under the prompt, indented
+    pub code: Option<String>,
+    pub kind: Kind,
+    //why the answer is what it is, shown whether the answer was right or not
+    pub why: String,
+}
+
+pub enum Kind {
+    Choice { options: Vec<String>, answer: usize },
+    TrueFalse(bool),
+    //any of these counts, ignoring case, spacing and `backticks`
+    Text(Vec<String>),
+}
+
+impl Question {
+    pub fn module(&self) -> &str {
+        self.id.rsplit_once("::").map_or(&self.id, |(module, _)| module)
+    }
+
+    //The right answer, written out
+    pub fn answer(&self) -> String {
+        match &self.kind {
+            Kind::Choice { options, answer } => format!("{}) {}", letter(*answer), options[*answer]),
+            Kind::TrueFalse(answer) => answer.to_string(),
+            Kind::Text(answers) => answers[0].clone(),
+        }
+    }
+
+    pub fn is_right(&self, reply: &str) -> bool {
+        let reply = normalize(reply);
+        match &self.kind {
+            Kind::Choice { options, answer } => {
+                let picked = match reply.parse::<usize>() {
+                    Ok(n) => n.checked_sub(1),
+                    Err(_) => options.iter().position(|o| normalize(o) == reply).or_else(|| from_letter(&reply)),
+                };
+                picked == Some(*answer)
+            }
+            Kind::TrueFalse(answer) => match reply.as_str() {
+                "t" | "true" | "y" | "yes" => *answer,
+                "f" | "false" | "n" | "no" => !*answer,
+                _ => false,
+            },
+            Kind::Text(answers) => answers.iter().any(|a| normalize(a) == reply),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Score {
+    pub right: usize,
+    pub asked: usize,
+}
+
+//Every question in the bank, or just one module's
+pub fn questions(module: Option<&str>) -> Vec<Question> {
+    MODULES.iter().filter(|m| module.is_none_or(|module| module == **m)).flat_map(|m| module_questions(m)).collect()
+}
+
+pub type Ids = HashMap<&'static str, Vec<String>>;
+
+//Every question's id, by module. The predict questions' ids come from what they're about,
+//so this doesn't run the lessons the way building the questions does
+pub fn ids() -> Ids {
+    let ids = |m: &str| bank::questions(m).into_iter().map(|q| q.id).chain(predict::ids(m)).collect();
+    MODULES.iter().map(|m| (*m, ids(m))).collect()
+}
+
+//The module's own questions, then the ones about what its functions print
+fn module_questions(module: &str) -> Vec<Question> {
+    let mut questions = bank::questions(module);
+    questions.extend(predict::questions(module));
+    questions
+}
+
+pub fn list(ctx: &mut Context) {
+    for module in MODULES {
+        say!(ctx, "{module:<30} {} questions", module_questions(module).len());
+    }
+}
+
+//Asks every question (q or running out of input stops early), then sums up how it went.
+//Every answer goes into the learner's progress as soon as it's given
+pub fn run(ctx: &mut Context, progress: &mut Progress, questions: &[Question]) -> Score {
+    let mut score = Score::default();
+    //right and asked, per module, in the order they came up
+    let mut modules: Vec<(&str, Score)> = Vec::new();
+
+    for (i, question) in questions.iter().enumerate() {
+        say!(ctx, "\nQuestion {} of {} ({})", i + 1, questions.len(), question.module());
+        let Some(right) = ask(ctx, question) else { break };
+        progress.answer(&question.id, right);
+
+        score.asked += 1;
+        score.right += right as usize;
+        match modules.iter_mut().find(|(m, _)| *m == question.module()) {
+            Some((_, s)) => {
+                s.asked += 1;
+                s.right += right as usize;
+            }
+            None => modules.push((question.module(), Score { right: right as usize, asked: 1 })),
+        }
+    }
+
+    say!(ctx);
+    if score.asked == 0 {
+        say!(ctx, "No questions answered.");
+        return score;
+    }
+    say!(ctx, "You got {} of {} right ({}%).", score.right, score.asked, score.right * 100 / score.asked);
+    if modules.len() > 1 {
+        for (module, s) in &modules {
+            say!(ctx, "  {module:<30} {} of {}", s.right, s.asked);
+        }
+    }
+    score
+}
+
+//Asks the questions that are due again, from every module, then says when the next ones will be
+pub fn review(ctx: &mut Context, progress: &mut Progress) {
+    let mut all = questions(None);
+    let due: Vec<String> = progress.due(progress::now()).iter().map(|a| a.question.clone()).collect();
+    //questions that have been taken out of the bank since just don't come up
+    let mut questions = Vec::new();
+    for id in &due {
+        if let Some(i) = all.iter().position(|q| q.id == *id) {
+            questions.push(all.swap_remove(i));
+        }
+    }
+
+    if questions.is_empty() {
+        match progress.next_due() {
+            Some(at) => say!(ctx, "Nothing to review yet. The next question is due {}.", progress::until(at)),
+            None => say!(ctx, "Nothing to review: answer some questions with `ownership quiz` first."),
+        }
+        return;
+    }
+    let plural = if questions.len() == 1 { "" } else { "s" };
+    say!(ctx, "{} question{plural} to review.", questions.len());
+    run(ctx, progress, &questions);
+    if let Some(at) = progress.next_due() {
+        say!(ctx, "Next review: {}.", progress::until(at));
+    }
+}
+
+//Asks one question and says right away whether the answer was right. None means the learner quit
+pub fn ask(ctx: &mut Context, question: &Question) -> Option<bool> {
+    say!(ctx, "{}", question.prompt);
+    if let Some(code) = &question.code {
+        say!(ctx);
+        for line in code.lines() {
+            say!(ctx, "    {line}");
+        }
+        say!(ctx);
+    }
+    match &question.kind {
+        Kind::Choice { options, .. } => {
+            for (i, option) in options.iter().enumerate() {
+                say!(ctx, "  {}) {option}", letter(i));
+            }
+        }
+        Kind::TrueFalse(_) => say!(ctx, "(true or false)"),
+        Kind::Text(_) => {}
+    }
+
+    let reply = ctx.input.read_line()?;
+    if matches!(reply.trim(), "q" | "quit") {
+        return None;
+    }
+    let right = question.is_right(&reply);
+    match right {
+        true => say!(ctx, "Right! {}", question.why),
+        false => say!(ctx, "Not quite: it's {}. {}", question.answer(), question.why),
+    }
+    Some(right)
+}
+
+fn letter(i: usize) -> char {
+    (b'a' + i as u8) as char
+}
+
+fn from_letter(reply: &str) -> Option<usize> {
+    match reply.as_bytes() {
+        [c @ b'a'..=b'z'] => Some((c - b'a') as usize),
+        _ => None,
+    }
+}
+
+//"  `**C`. " -> "**c"
+fn normalize(text: &str) -> String {
+    let text = text.trim().trim_end_matches('.').trim_matches(['`', '"']);
+    text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_match_the_questions_they_stand_for() {
+        let ids = ids();
+        for module in MODULES {
+            let built: Vec<String> = module_questions(module).into_iter().map(|q| q.id).collect();
+            assert_eq!(ids[module], built, "{module}");
+        }
+    }
+}
@@ -0,0 +1,328 @@
+use super::{Kind, Question};
+
+//Every lesson module that has questions, in the order the lessons go
+pub const MODULES: [&str; 5] = ["ownership", "references_and_borrowing", "slice", "vectors", "ownership_case_study"];
+
+pub fn questions(module: &str) -> Vec<Question> {
+    match module {
+        "ownership" => ownership(),
+        "references_and_borrowing" => references_and_borrowing(),
+        "slice" => slice(),
+        "vectors" => vectors(),
+        "ownership_case_study" => ownership_case_study(),
+        _ => Vec::new(),
+    }
+}
+
+fn choice(id: &str, prompt: &str, code: Option<&str>, options: &[&str], answer: usize, why: &str) -> Question {
+    let options = options.iter().map(|o| o.to_string()).collect();
+    question(id, prompt, code, Kind::Choice { options, answer }, why)
+}
+
+fn true_false(id: &str, prompt: &str, code: Option<&str>, answer: bool, why: &str) -> Question {
+    question(id, prompt, code, Kind::TrueFalse(answer), why)
+}
+
+fn text(id: &str, prompt: &str, code: Option<&str>, answers: &[&str], why: &str) -> Question {
+    question(id, prompt, code, Kind::Text(answers.iter().map(|a| a.to_string()).collect()), why)
+}
+
+fn question(id: &str, prompt: &str, code: Option<&str>, kind: Kind, why: &str) -> Question {
+    Question { id: id.to_string(), prompt: prompt.to_string(), code: code.map(String::from), kind, why: why.to_string() }
+}
+
+const ADD_TEN: &str = "\
+let no_im_not = String::from(\"nine\");
+let stupid = add_ten(no_im_not);
+println!(\"{no_im_not}\");";
+
+fn ownership() -> Vec<Question> {
+    vec![
+        choice(
+            "ownership::use_after_move",
+            "What happens on the last line?",
+            Some(ADD_TEN),
+            &["it prints nine", "it prints nine plus ten", "it doesn't compile: no_im_not was moved", "it prints whatever is left in freed memory"],
+            2,
+            "add_ten took ownership of the String, so no_im_not can't be used anymore (E0382).",
+        ),
+        true_false(
+            "ownership::push_str_reallocs",
+            "Inside add_ten, number.push_str(\" plus ten\") can put the String somewhere new on the heap and free the old allocation.",
+            None,
+            true,
+            "When a String outgrows its capacity it reallocates, which is why no_im_not would point at freed memory if Rust let you use it.",
+        ),
+        choice(
+            "ownership::make_it_safe",
+            "How does make_it_safe keep quick_maths usable after calling add_ten?",
+            None,
+            &["it passes add_ten a reference", "it clones quick_maths and moves the clone", "add_ten gives ownership back", "it puts quick_maths in a Box"],
+            1,
+            "Only quick_maths_clone moves into add_ten, so quick_maths still owns its own String (but the clone is gone).",
+        ),
+        text(
+            "ownership::moved_value_code",
+            "What error code does rustc give for using a value after it moved?",
+            None,
+            &["E0382"],
+            "E0382 is \"borrow of moved value\".",
+        ),
+        true_false(
+            "ownership::checked_when",
+            "Rust finds undefined behavior like using freed memory while the program runs.",
+            None,
+            false,
+            "Ownership is checked at compile time, so the program doesn't pay for checks while it runs.",
+        ),
+    ]
+}
+
+const DEREFERENCING: &str = "\
+let mut a: Box<i32> = Box::new(1);
+let b: i32 = *a;
+*a += 1;
+let c: &Box<i32> = &a;
+let d: i32 = **c;";
+
+const ALIASING: &str = "\
+let mut vec: Vec<i32> = vec![1, 2, 3];
+let num: &i32 = &vec[2];
+vec.push(4);
+println!(\"Third element is {}\", *num);";
+
+fn references_and_borrowing() -> Vec<Question> {
+    vec![
+        text(
+            "references_and_borrowing::double_deref",
+            "What does **c evaluate to?",
+            Some(DEREFERENCING),
+            &["2"],
+            "c points to a on the stack, a points to the heap: *c is a, and **c is the 2 that *a += 1 left there.",
+        ),
+        text(
+            "references_and_borrowing::copied_out",
+            "What's in b after the last line?",
+            Some(DEREFERENCING),
+            &["1"],
+            "let b = *a copied the 1 out of the heap before *a += 1 changed it.",
+        ),
+        choice(
+            "references_and_borrowing::ref_to_box",
+            "What does c point to?",
+            Some(DEREFERENCING),
+            &["the i32 on the heap", "a, on the stack", "b", "a copy of the Box"],
+            1,
+            "&a points at the variable a itself. To point at the heap value you'd write &*a, like e does.",
+        ),
+        choice(
+            "references_and_borrowing::push_while_borrowed",
+            "Why doesn't this compile?",
+            Some(ALIASING),
+            &[
+                "vec isn't mutable",
+                "push can reallocate the Vec, leaving num pointing at freed memory",
+                "num is a mutable reference",
+                "index 2 is out of bounds",
+            ],
+            1,
+            "num is still used on the last line, so vec is still borrowed when push needs to write to it (E0502).",
+        ),
+        true_false(
+            "references_and_borrowing::mutable_takes_all",
+            "While a mutable reference to ids[2] is alive, ids loses its R, W and O permissions.",
+            None,
+            true,
+            "A mutable reference has to be the only way to reach the data, so even reading ids has to wait.",
+        ),
+        choice(
+            "references_and_borrowing::handoff",
+            "After let ref_2 = &*ref_1;, when does original get its permissions back?",
+            Some("let mut original = Box::new([1; 3]);\nlet ref_1 = &mut original[1];\n*ref_1 = 5;\nlet ref_2 = &*ref_1;\nassert_eq!(*ref_2, 5);\n(*original)[1] = 1;"),
+            &["right away", "after ref_1's last use", "after ref_2's last use", "at the end of main"],
+            2,
+            "ref_1 dies when ref_2 is made, but ref_2 borrows through it, so original waits for ref_2's last use.",
+        ),
+        text(
+            "references_and_borrowing::drop_needs",
+            "drop(oh_no) doesn't compile while danger = &oh_no is alive. Which permission does drop need that danger took away (R, W, O or F)?",
+            None,
+            &["O", "own"],
+            "Dropping needs ownership, and borrowing oh_no takes its O permission until danger's last use.",
+        ),
+        true_false(
+            "references_and_borrowing::implicit_deref",
+            "x.abs() works on a Box<i32> because the dot operator dereferences x for you.",
+            None,
+            true,
+            "It's the same as i32::abs(*x), with the * added implicitly.",
+        ),
+    ]
+}
+
+fn slice() -> Vec<Question> {
+    vec![
+        choice(
+            "slice::fat_pointer",
+            "What does a &str carry that a &String doesn't?",
+            None,
+            &["the capacity", "the length of the slice", "a reference count", "nothing, they're the same size"],
+            1,
+            "Slices are fat pointers: a pointer plus the length, so they take an extra 8 bytes.",
+        ),
+        text(
+            "slice::second_half",
+            "string is \"会ったことがない|Never met 'em\". What's slice2?",
+            Some("let sep_index = string.find('|').unwrap();\nlet slice: &str = &string[0..sep_index];\nlet slice2: &str = &string[sep_index + 1..string.len()];"),
+            &["Never met 'em"],
+            "slice2 starts right after the | and goes to the end.",
+        ),
+        true_false(
+            "slice::full_slice",
+            "&string[..] is a slice of the whole string.",
+            None,
+            true,
+            "A range can leave out its start (0) and its end (the length).",
+        ),
+        choice(
+            "slice::clear_while_sliced",
+            "Why can't ecils_backwards be printed after generic_string.clear()?",
+            Some("let ecils_backwards: &str = gimme_a_slice(&generic_string);\ngeneric_string.clear();\nprintln!(\"{ecils_backwards}\");"),
+            &[
+                "ecils_backwards is a copy, so it would be out of date",
+                "clear needs to borrow generic_string mutably while the slice still borrows it",
+                "Strings can't be cleared",
+                "slices only live until the next line",
+            ],
+            1,
+            "The slice is tied to generic_string, and clearing it while the slice is alive would leave the slice pointing at nothing.",
+        ),
+        text(
+            "slice::literal_type",
+            "What's the type of a string literal like \"a literal slice\"?",
+            None,
+            &["&str", "&'static str"],
+            "String literals are slices pointing into the program's binary, which is also why they can't change.",
+        ),
+    ]
+}
+
+fn vectors() -> Vec<Question> {
+    vec![
+        true_false(
+            "vectors::on_the_heap",
+            "A Vec's elements are stored on the heap.",
+            None,
+            true,
+            "That's why a Vec can change length, unlike an array.",
+        ),
+        choice(
+            "vectors::past_capacity",
+            "What happens when you push onto a Vec that's already at its capacity?",
+            None,
+            &[
+                "it panics",
+                "it allocates more memory, moves the elements there and frees the old memory",
+                "it overwrites the last element",
+                "it doesn't compile",
+            ],
+            1,
+            "This is why a reference into a Vec can't be alive across a push.",
+        ),
+        text(
+            "vectors::weekdays_len",
+            "How long is weekdays at the end?",
+            Some("let mut weekdays: Vec<char> = vec!['日', '月', '火', '水', '木'];\nweekdays.push('金');\nVec::push(&mut weekdays, '土');"),
+            &["7"],
+            "Five to start with, and both ways of calling push add one.",
+        ),
+        true_false(
+            "vectors::array_push",
+            "An array like [char; 5] can grow with push too.",
+            None,
+            false,
+            "An array's length is part of its type. Use vec![] when it needs to grow.",
+        ),
+    ]
+}
+
+const UNSAFE_COPY: &str = "\
+let v: Vec<String> = vec![String::from(\"Hello\"), String::from(\"World\")];
+let s_ref: &String = &v[0];
+let s = *s_ref;";
+
+fn ownership_case_study() -> Vec<Question> {
+    vec![
+        choice(
+            "ownership_case_study::unsafe_copy",
+            "Why is *s_ref rejected in unsafe_copy?",
+            Some(UNSAFE_COPY),
+            &[
+                "s_ref points at freed memory",
+                "it moves the String out through a reference, so v and s would both free \"Hello\"",
+                "v isn't mutable",
+                "a String can't be stored in a Vec",
+            ],
+            1,
+            "References don't own what they point to, so ownership can't be taken through them.",
+        ),
+        text(
+            "ownership_case_study::unsafe_copy_code",
+            "What error code does rustc give for *s_ref in unsafe_copy?",
+            Some(UNSAFE_COPY),
+            &["E0507"],
+            "E0507 is \"cannot move out of a shared reference\".",
+        ),
+        true_false(
+            "ownership_case_study::safe_copy",
+            "In safe_copy, *n_ref is fine because it copies the i32 instead of moving it.",
+            Some("let v: Vec<i32> = vec![0, 1, 2];\nlet n_ref: &i32 = &v[0];\n*n_ref"),
+            true,
+            "i32 implements Copy: it owns no heap data, so copying it out can't lead to a double free.",
+        ),
+        choice(
+            "ownership_case_study::return_a_string",
+            "Which of these doesn't fix return_a_string?",
+            None,
+            &["return the String itself", "return a &'static str", "return an Rc<String>", "keep returning &s, with a lifetime annotation"],
+            3,
+            "s still dies at the end of the function no matter what the annotation says.",
+        ),
+        true_false(
+            "ownership_case_study::tuple_fields",
+            "rustc rejects let first = &name.0; followed by name.1.push_str(\", Esq\"); in the same function.",
+            None,
+            false,
+            "rustc tells tuple fields apart. It only rejects this when the borrow goes through get_first, whose body it doesn't look at.",
+        ),
+        choice(
+            "ownership_case_study::array_index",
+            "In case 6, how does rustc see &mut a[0] and &a[1]?",
+            None,
+            &["as two separate places", "as the same place, a[_]", "as out of bounds", "as copies"],
+            1,
+            "The borrow checker doesn't tell indexes apart, so the safe program is rejected.",
+        ),
+        choice(
+            "ownership_case_study::clone_largest",
+            "Why does semi_solution3_1 clone largest?",
+            Some("let largest: String = dst.iter().max_by_key(|s| s.len()).unwrap().clone();"),
+            &[
+                "so largest owns its own String, and dst isn't borrowed anymore when it's pushed to",
+                "because Strings can't be compared without cloning",
+                "so the largest string can be pushed twice",
+                "because max_by_key moves the string out of dst",
+            ],
+            0,
+            "A clone doesn't point into dst, so dst keeps its W permission. It works, but cloning a big string costs time.",
+        ),
+        choice(
+            "ownership_case_study::largest_len",
+            "What's the best fix for add_big_strings?",
+            None,
+            &["clone largest", "collect the strings to add first, then extend dst", "keep only largest's length", "take dst by value"],
+            2,
+            "largest_len isn't a reference, so it doesn't keep dst borrowed, and nothing gets cloned.",
+        ),
+    ]
+}
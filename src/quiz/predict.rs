@@ -0,0 +1,78 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use super::{Kind, Question};
+use crate::input::Scripted;
+use crate::lesson::{self, Context};
+use crate::output::Buffer;
+use crate::source;
+
+//"What does this print?" questions. The learner reads a function's source, and the answer is
+//whatever the lesson really printed when it ran, so it can't fall out of date with the code
+
+//(the function to show, the lesson that runs it, the question, which line of the lesson's output it printed)
+type Prediction = (&'static str, &'static str, &'static str, fn(&str) -> bool);
+
+const PREDICTIONS: [Prediction; 3] = [
+    (
+        "ownership_case_study::safe_copy",
+        "ownership_case_study::case4",
+        "case4 prints what safe_copy returns. What does it print?",
+        |line| line.parse::<i32>().is_ok(),
+    ),
+    (
+        "references_and_borrowing::explain_dereferencing",
+        "references_and_borrowing",
+        "What does the say! line print?",
+        |line| line.starts_with("a: "),
+    ),
+    (
+        "slice::showcase_string_slices",
+        "slice",
+        "What's the first line it prints?",
+        |line| line.starts_with("This is what we cut off: "),
+    ),
+];
+
+pub fn questions(module: &str) -> Vec<Question> {
+    let mut questions = Vec::new();
+    for (function, lesson, prompt, printed) in PREDICTIONS {
+        let (from, name) = function.split_once("::").expect("predictions name module::function");
+        if from != module {
+            continue;
+        }
+        //a function that was renamed or a line that's gone just means one less question
+        let Some(code) = source::function(function) else { continue };
+        let output = capture(lesson);
+        let Some(line) = output.lines().find(|l| printed(l)) else { continue };
+
+        questions.push(Question {
+            id: format!("{module}::predict_{name}"),
+            prompt: prompt.to_string(),
+            code: Some(code.to_string()),
+            kind: Kind::Text(vec![line.to_string()]),
+            why: format!("That's what {lesson} printed when it ran."),
+        });
+    }
+    questions
+}
+
+//The ids questions() gives its questions, without running any lessons to get the answers
+pub fn ids(module: &str) -> Vec<String> {
+    PREDICTIONS
+        .iter()
+        .filter_map(|(function, ..)| function.split_once("::"))
+        .filter(|(from, _)| *from == module)
+        .map(|(_, name)| format!("{module}::predict_{name}"))
+        .collect()
+}
+
+//Runs a lesson the same way every time (no answers typed in, seed 1, no rustc) and keeps what it printed
+fn capture(name: &str) -> String {
+    let lessons = lesson::registry();
+    let Some(lesson) = lesson::find(&lessons, name) else { return String::new() };
+    let buffer = Buffer::new();
+    let mut ctx = Context::new(Box::new(Scripted::new(Vec::<String>::new())), Box::new(buffer.clone()), StdRng::seed_from_u64(1));
+    ctx.diagnostics = false;
+    lesson.run(&mut ctx);
+    buffer.contents()
+}
@@ -1,35 +1,36 @@
 #![allow(dead_code)]
+#![allow(clippy::borrowed_box, clippy::explicit_auto_deref, clippy::neg_multiply)]
 
-use std::io;
 use rand::Rng;
+use crate::diagnostics;
+use crate::lesson::Context;
+use crate::output::say;
+use crate::permissions::{self, Line, Op};
 use crate::vectors;
 
 //Move-only API programming on the heap is inconvenient
-pub fn explain() {
-    let mut greeting = String::new();
-    let mut farewell = String::new();
-    
-    println!("Enter a greeting: ");
-    io::stdin().read_line(&mut greeting).expect("Input should have been read"); 
-    greeting = String::from(greeting.trim());
+pub fn explain(ctx: &mut Context) {
+    say!(ctx, "Enter a greeting: ");
+    let greeting = String::from(ctx.read_line().trim());
 
-    println!("Enter a farewell: ");
-    io::stdin().read_line(&mut farewell).expect("Input should have been read");
-    farewell = String::from(farewell.trim());
+    say!(ctx, "Enter a farewell: ");
+    let farewell = String::from(ctx.read_line().trim());
 
     //(greeting, farewell) = 
-        greet_farewell(greeting, farewell);
+        greet_farewell(ctx, greeting, farewell);
     
     //println!("Good morning, {greeting}, Good night, {farewell}");
     //This is inconvenient error. I can only say hi and bye once in my entire life?
-    explain_references();
+    say!(ctx, "Saying hi and bye again doesn't compile:");
+    diagnostics::show(ctx, "greet_after_move");
+    explain_references(ctx);
 }
 
-fn greet_farewell(str1: String, str2: String) //-> (String, String) 
+fn greet_farewell(ctx: &mut Context, str1: String, str2: String) //-> (String, String) 
 {
     let mut combined = str1.clone();
     combined.push_str(&str2);
-    println!("{combined}!");
+    say!(ctx, "{combined}!");
 
     //You could return ownership of the strings to prevent this, but that's lame
     //(str1, str2)
@@ -37,33 +38,30 @@ fn greet_farewell(str1: String, str2: String) //-> (String, String)
 
 
 //
-fn explain_references() {
-    let mut input = String::new();
-
-    println!("Enter an exciting word: ");
-    io::stdin().read_line(&mut input).expect("Expected to get input");
-    let input = String::from(input.trim());
+fn explain_references(ctx: &mut Context) {
+    say!(ctx, "Enter an exciting word: ");
+    let input = String::from(ctx.read_line().trim());
 
     //What's that ampersand (&) doing there?
-    emphasize(&input);
-    println!("Who cares. It's just {input}.");
+    emphasize(ctx, &input);
+    say!(ctx, "Who cares. It's just {input}.");
 
     //& marks a reference, which is a type of pointer
-    explain_dereferencing();
+    explain_dereferencing(ctx);
 }
 
 
 //Another ampersand (&)
-fn emphasize(word: &String) {
+fn emphasize(ctx: &mut Context, word: &String) {
     //instead of taking ownership, this function creates a reference to the input variable
     //That means once this function ends, nothing is dealloc'd
-    println!("Can you believe it guys? It's {word}!!!");
+    say!(ctx, "Can you believe it guys? It's {word}!!!");
 
     //References are non-owning pointers, so using a reference is also called 'borrowing'
 }
 
 
-fn explain_dereferencing() {
+fn explain_dereferencing(ctx: &mut Context) {
     //println! can work with Strings and &Strings due to dereferencing (*): follows reference to data
 
     //Remember, a box is just memory on the heap, so a Box as a variable points to that memory
@@ -80,7 +78,7 @@ fn explain_dereferencing() {
     let f: i32 = *e;        //so we grab the value
 
     
-    println!("a: {a}, b: {b}, c: {c}, d: {d}, e: {e}, f: {f}");
+    say!(ctx, "a: {a}, b: {b}, c: {c}, d: {d}, e: {e}, f: {f}");
 
     //Think of * as an arrow, so you travel to the thing a pointer points to
     //Think of & as a U turn, so you take a few steps back and point to the thing
@@ -96,11 +94,11 @@ fn explain_dereferencing() {
     let str_len2 = str.len();  //implicit reference
     assert_eq!(str_len1, str_len2);
 
-    explain_aliasing();
+    explain_aliasing(ctx);
 }
 
 
-fn explain_aliasing() {
+fn explain_aliasing(ctx: &mut Context) {
     //Pointers are scary because they allow simultaneous aliasing and mutation
 
     //aliasing is accessing the same data through different variables
@@ -110,16 +108,16 @@ fn explain_aliasing() {
     let box_value2 = **box_reference;
 
     if box_value1 == box_value2 {
-        println!("They're the same.");    
+        say!(ctx, "They're the same.");    
     }
 
     if box_value1 == *a_box {
-        println!("These are also the same.");    
+        say!(ctx, "These are also the same.");    
     }
 
     //if aliased variables are changed or dealloc'd carelessly, can cause unsafe behavior
     //The next examples will use vectors, so learn abt them
-    vectors::explain();
+    vectors::explain(ctx);
 
     //Vectors have a length and a capacity
     //When you push to a vector and it goes above capacity, 
@@ -129,20 +127,30 @@ fn explain_aliasing() {
     let mut vec: Vec<i32> = vec![1, 2, 3];
     let num: &i32 = &vec[2];
     //vec.push(4);  //Not ok: num is still in use
-    println!("Third element is {}", *num);  //num no longer in use
+    say!(ctx, "Pushing while num is still in use doesn't compile:");
+    diagnostics::show(ctx, "push_while_borrowed");
+    say!(ctx, "Third element is {}", *num);  //num no longer in use
     vec.push(4);  //This is ok: num isn't used anymore
 
+    say!(ctx, "What happened to the permissions, line by line:");
+    permissions::show(ctx, &[
+        Line { code: "let mut vec: Vec<i32> = vec![1, 2, 3];", ops: vec![Op::Own { name: "vec", mutable: true, indexed: true }] },
+        Line { code: "let num: &i32 = &vec[2];", ops: vec![Op::Borrow { name: "num", place: "vec[2]", mutable: false, binding_mut: false }] },
+        Line { code: "println!(\"Third element is {}\", *num);", ops: vec![Op::Die("num")] },
+        Line { code: "vec.push(4);", ops: vec![] },
+    ]);
+
     //POINTER SAFETY PRINCIPLE: Data should never be aliased and mutated at the same time.
 
     //With boxes, this is easy - you can't alias with a box
     //With references and borrowing, the BORROW CHECKER is used to ensure memory safety (check slides)
 
-    explain_mutable_references();   
+    explain_mutable_references(ctx);   
 }
 
 
 //also known as unique references
-fn explain_mutable_references() {
+fn explain_mutable_references(ctx: &mut Context) {
     //before we used immutable (or shared) references
     //sometimes we want to change data without moving it
     let mut ids: Vec<u64> = vec![91488919, 48199402, 0, 1881002, 777];
@@ -150,10 +158,12 @@ fn explain_mutable_references() {
 
     //Now that we have a mutable reference, all the vector's Read Write Own perms are gone
     //println!("Found invalid id: {}", ids[2]);
+    say!(ctx, "Reading ids while num is alive doesn't compile:");
+    diagnostics::show(ctx, "read_while_mutably_borrowed");
 
     //In exchange, now the deref'd path can be written to
-    *num = rand::thread_rng().gen_range(1..=u64::MAX);
-    println!("Changed third id to be valid: {}", ids[2]);
+    *num = ctx.rng.gen_range(1..=u64::MAX);
+    say!(ctx, "Changed third id to be valid: {}", ids[2]);
 
     //Basically, mutable references prevent aliasing by removing RWO perms from original reference
     //mutable references can be turned immutable
@@ -169,21 +179,39 @@ fn explain_mutable_references() {
 
     //How DARE you try to desecrate our Lord! (cannot be assigned because it is BORROWED)
     //*god_ref = 666;
+    say!(ctx, "Assigning to god_ref while it's borrowed doesn't compile:");
+    diagnostics::show(ctx, "assign_while_reborrowed");
 
     //So we can alias! (vector doesn't get its RWO perms back until god_ref is gone though)
-    println!("Here, I made a number to represent God: {}", *god_ref);
+    say!(ctx, "Here, I made a number to represent God: {}", *god_ref);
     //println!("Did I mention God is {}?", ids[4]);
-    println!("Thank our Lord and Savior {immutable_god_ref} for Rust!");
+    say!(ctx, "Reading ids before god_ref is gone doesn't compile:");
+    diagnostics::show(ctx, "read_vec_while_god_ref_lives");
+    say!(ctx, "Thank our Lord and Savior {immutable_god_ref} for Rust!");
     
     //Finally, perms!
     ids[0] = 1;
 
-    explain_lifetimes();
+    say!(ctx, "Where the permissions went:");
+    permissions::show(ctx, &[
+        Line { code: "let mut ids: Vec<u64> = vec![...];", ops: vec![Op::Own { name: "ids", mutable: true, indexed: true }] },
+        Line { code: "let num: &mut u64 = &mut ids[2];", ops: vec![Op::Borrow { name: "num", place: "ids[2]", mutable: true, binding_mut: false }] },
+        Line { code: "*num = rng.gen_range(1..=u64::MAX);", ops: vec![Op::Die("num")] },
+        Line { code: "let god_ref = &mut ids[4];", ops: vec![Op::Borrow { name: "god_ref", place: "ids[4]", mutable: true, binding_mut: false }] },
+        Line { code: "*god_ref += 1;", ops: vec![] },
+        Line { code: "*god_ref -= 1;", ops: vec![] },
+        Line { code: "let immutable_god_ref = &*god_ref;", ops: vec![Op::Borrow { name: "immutable_god_ref", place: "*god_ref", mutable: false, binding_mut: false }] },
+        Line { code: "println!(\"...{}\", *god_ref);", ops: vec![Op::Die("god_ref")] },
+        Line { code: "println!(\"...{immutable_god_ref}...\");", ops: vec![Op::Die("immutable_god_ref")] },
+        Line { code: "ids[0] = 1;", ops: vec![] },
+    ]);
+
+    explain_lifetimes(ctx);
 }
 
 
 //Permissions are returned to the original path to a value in the heap when all other paths (references) die.
-fn explain_lifetimes() {
+fn explain_lifetimes(ctx: &mut Context) {
     let mut original: Box<[i32; 3]> = Box::new([1; 3]);
 
     //Hey, implicit dereference!
@@ -197,11 +225,21 @@ fn explain_lifetimes() {
     //original gains back perms
     (*original)[1] = 1;
 
+    say!(ctx, "ref_1 hands off to ref_2, and original only gets its perms back when ref_2 is done:");
+    permissions::show(ctx, &[
+        Line { code: "let mut original = Box::new([1; 3]);", ops: vec![Op::Own { name: "original", mutable: true, indexed: true }] },
+        Line { code: "let ref_1 = &mut original[1];", ops: vec![Op::Borrow { name: "ref_1", place: "original[1]", mutable: true, binding_mut: false }] },
+        Line { code: "*ref_1 = 5;", ops: vec![] },
+        Line { code: "let ref_2 = &*ref_1;", ops: vec![Op::Borrow { name: "ref_2", place: "*ref_1", mutable: false, binding_mut: false }, Op::Die("ref_1")] },
+        Line { code: "assert_eq!(*ref_2, 5);", ops: vec![Op::Die("ref_2")] },
+        Line { code: "(*original)[1] = 1;", ops: vec![] },
+    ]);
+
     //With control flow, a lifetime doesn't have to be defined for a full block of code
     let mut pos_or_neg: Box<[i8; 10]> = Box::new([1, -2, 3, 4, -5, -6, 7, 8, 9, -10]);
     
     //Another implicit dereference!
-    let rng_i = rand::thread_rng().gen_range(0..pos_or_neg.len());
+    let rng_i = ctx.rng.gen_range(0..pos_or_neg.len());
 
     //And another implcit dereference! Should be (*pos_or_neg)[rng_i]
     let rng_value = pos_or_neg[rng_i];
@@ -212,24 +250,44 @@ fn explain_lifetimes() {
         let conditional_ref = &mut pos_or_neg[rng_i];
         *conditional_ref = rng_value * -1;
 
-        println!("Changed position {rng_i} to be negative (now {conditional_ref})");
+        say!(ctx, "Changed position {rng_i} to be negative (now {conditional_ref})");
     } else {
-        println!("Position {rng_i} ({rng_value}) is already negative");
+        say!(ctx, "Position {rng_i} ({rng_value}) is already negative");
     }    
 
-    explain_data_outliving_references();
+    say!(ctx, "Either way, pos_or_neg only loses its perms inside the if:");
+    permissions::show(ctx, &[
+        Line { code: "let mut pos_or_neg = Box::new([1, -2, ...]);", ops: vec![Op::Own { name: "pos_or_neg", mutable: true, indexed: true }] },
+        Line { code: "if rng_value.is_positive() {", ops: vec![] },
+        Line { code: "    let conditional_ref = &mut pos_or_neg[rng_i];", ops: vec![Op::Borrow { name: "conditional_ref", place: "pos_or_neg[rng_i]", mutable: true, binding_mut: false }] },
+        Line { code: "    *conditional_ref = rng_value * -1;", ops: vec![] },
+        Line { code: "    println!(\"...{conditional_ref})\");", ops: vec![Op::Die("conditional_ref")] },
+        Line { code: "} else {", ops: vec![] },
+        Line { code: "    println!(\"...already negative\");", ops: vec![] },
+        Line { code: "}", ops: vec![] },
+    ]);
+
+    explain_data_outliving_references(ctx);
 }
 
 
 //Keep in mind - being set to nil is still a mutation.
-fn explain_data_outliving_references() {
+fn explain_data_outliving_references(ctx: &mut Context) {
     //When Rust knows how long a reference lives, it just uses the Own permission
     let oh_no = String::from("I'm in danger.");
     let danger = &oh_no;
 
     //The borrow removes oh_no's O permission, but drop requires that, so the program doesn't compile
     //drop(oh_no);
-    println!("{}", *danger);
+    say!(ctx, "Dropping oh_no while danger points to it doesn't compile:");
+    diagnostics::show(ctx, "drop_while_borrowed");
+    say!(ctx, "because drop needs the O permission, and danger took it:");
+    permissions::show(ctx, &[
+        Line { code: "let oh_no = String::from(\"I'm in danger.\");", ops: vec![Op::Own { name: "oh_no", mutable: false, indexed: false }] },
+        Line { code: "let danger = &oh_no;", ops: vec![Op::Borrow { name: "danger", place: "oh_no", mutable: false, binding_mut: false }] },
+        Line { code: "println!(\"{}\", *danger);", ops: vec![Op::Die("danger")] },
+    ]);
+    say!(ctx, "{}", *danger);
 
     //If Rust doesn't know a reference's lifetime (like when it the input or output from a function), uses Flow
     let why = &mut vec![9, 10];
@@ -241,6 +299,20 @@ fn explain_data_outliving_references() {
     //This is unsafe, but ONLY if flow doesn't return &50
     //Since it's uncertain, this won't compile
     //println!("{w}");
+    say!(ctx, "flow's signature alone doesn't say which input the output comes from:");
+    diagnostics::show(ctx, "flow");
+
+    say!(ctx, "So neither of flow's inputs gets the F permission:");
+    permissions::show(ctx, &[
+        Line {
+            code: "fn flow(im: &Vec<i32>, confused: &i32) -> &i32",
+            ops: vec![Op::Param { name: "im", mutable: false, flows: false }, Op::Param { name: "confused", mutable: false, flows: false }],
+        },
+    ]);
+    say!(ctx, "But with only one input, the output has to come from it:");
+    permissions::show(ctx, &[
+        Line { code: "fn first(im: &Vec<i32>) -> &i32", ops: vec![Op::Param { name: "im", mutable: false, flows: true }] },
+    ]);
 }
 
 
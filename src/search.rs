@@ -0,0 +1,267 @@
+use crate::glossary;
+use crate::lesson::{Context, Lesson};
+use crate::output::say;
+use crate::quiz;
+use crate::source;
+
+//Searches what the lessons print, the comments in their source, the glossary and the quiz.
+//Words are matched by their stem, so "realloc" also finds "alloc", "deallocs" and "allocation"
+
+const MAX_RESULTS: usize = 10;
+const MAX_LINES: usize = 3;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Prints,
+    Comment,
+    Glossary,
+    Quiz,
+}
+
+impl Kind {
+    fn label(self) -> &'static str {
+        match self {
+            Kind::Prints => "prints",
+            Kind::Comment => "comment",
+            Kind::Glossary => "glossary",
+            Kind::Quiz => "quiz",
+        }
+    }
+
+    //a match in a definition says more than one in a passing comment
+    fn weight(self) -> usize {
+        match self {
+            Kind::Glossary => 3,
+            Kind::Prints | Kind::Comment => 2,
+            Kind::Quiz => 1,
+        }
+    }
+}
+
+struct Entry {
+    //"ownership::add_ten" for lesson code and glossary terms, "quiz ownership::use_after_move" for questions
+    at: String,
+    kind: Kind,
+    text: String,
+}
+
+//Every hit in one place, and how well they matched all together
+struct Found<'a> {
+    at: &'a str,
+    score: usize,
+    hits: Vec<&'a Entry>,
+}
+
+pub fn run(ctx: &mut Context, lessons: &[Box<dyn Lesson>], query: &str) {
+    let words: Vec<&str> = query.split(|c: char| !is_word(c)).filter(|w| !w.is_empty()).collect();
+    if words.is_empty() {
+        say!(ctx, "Search for what? Try `ownership search realloc`.");
+        return;
+    }
+
+    let entries = index(lessons);
+    let mut results: Vec<Found> = Vec::new();
+    for entry in &entries {
+        let Some(score) = score(&words, &entry.text) else { continue };
+        let score = score * entry.kind.weight();
+        match results.iter_mut().find(|r| r.at == entry.at) {
+            Some(result) => {
+                result.score += score;
+                result.hits.push(entry);
+            }
+            None => results.push(Found { at: &entry.at, score, hits: vec![entry] }),
+        }
+    }
+    //ties stay in lesson order
+    results.sort_by_key(|r| std::cmp::Reverse(r.score));
+
+    if results.is_empty() {
+        say!(ctx, "Nothing about \"{query}\" in the lessons, glossary or quiz.");
+        return;
+    }
+    let plural = if results.len() == 1 { "" } else { "s" };
+    say!(ctx, "\"{query}\" comes up in {} place{plural}:", results.len());
+    for (i, result) in results.iter().take(MAX_RESULTS).enumerate() {
+        say!(ctx, "\n{:>2}. {}  ({})", i + 1, result.at, how_to_see(lessons, result.at));
+        for hit in result.hits.iter().take(MAX_LINES) {
+            say!(ctx, "      {:<9}{}", hit.kind.label(), shorten(&hit.text));
+        }
+        if result.hits.len() > MAX_LINES {
+            say!(ctx, "      (and {} more)", result.hits.len() - MAX_LINES);
+        }
+    }
+    if results.len() > MAX_RESULTS {
+        say!(ctx, "\n(and {} more places)", results.len() - MAX_RESULTS);
+    }
+}
+
+//How well the text matches every word in the query, or None if one of them isn't there
+fn score(query: &[&str], text: &str) -> Option<usize> {
+    let words: Vec<String> = text.split(|c: char| !is_word(c)).filter(|w| !w.is_empty()).map(str::to_lowercase).collect();
+    let mut total = 0;
+    for q in query {
+        let q = q.to_lowercase();
+        let q_stem = stem(&q);
+        let best = words
+            .iter()
+            .map(|w| match w {
+                w if *w == q => 3,
+                w if stem(w) == q_stem => 2,
+                w if stem(w).starts_with(&q_stem) => 1,
+                _ => 0,
+            })
+            .max()?;
+        if best == 0 {
+            return None;
+        }
+        total += best;
+    }
+    Some(total)
+}
+
+//Close enough to a stem for this: "deallocs" -> "alloc", "moved" -> "mov", "lifetimes" -> "lifetim"
+fn stem(word: &str) -> String {
+    let mut word = word.to_lowercase();
+    for suffix in ["ing", "ed", "es", "s"] {
+        if word.len() > suffix.len() + 2 && word.ends_with(suffix) && !word.ends_with("ss") {
+            word.truncate(word.len() - suffix.len());
+            break;
+        }
+    }
+    if word.len() > 3 && word.ends_with('e') {
+        word.pop();
+    }
+    //only when there's a real word left, so "remove" doesn't turn into "move"
+    for prefix in ["re", "de"] {
+        if word.len() >= prefix.len() + 5 && word.starts_with(prefix) {
+            word.drain(..prefix.len());
+            break;
+        }
+    }
+    word
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn shorten(text: &str) -> String {
+    match text.char_indices().nth(90) {
+        Some((at, _)) => format!("{}...", &text[..at]),
+        None => text.to_string(),
+    }
+}
+
+fn how_to_see(lessons: &[Box<dyn Lesson>], at: &str) -> String {
+    if let Some(id) = at.strip_prefix("quiz ") {
+        let module = id.rsplit_once("::").map_or(id, |(m, _)| m);
+        return format!("`ownership quiz {module}`");
+    }
+    match lesson_of(lessons, at) {
+        Some(lesson) => format!("`ownership run {lesson}`"),
+        None => String::from("not part of a lesson"),
+    }
+}
+
+//The lesson that gets to a function: the one whose entry is the closest above it in the same file
+fn lesson_of(lessons: &[Box<dyn Lesson>], function: &str) -> Option<&'static str> {
+    let (module, _) = function.split_once("::")?;
+    let src = source::file(module)?;
+    let at = src.find(source::function(function)?)?;
+    lessons
+        .iter()
+        .filter(|l| l.entry().starts_with(&format!("{module}::")))
+        .filter_map(|l| Some((src.find(source::function(l.entry())?)?, l.name())))
+        .filter(|(entry, _)| *entry <= at)
+        .max_by_key(|(entry, _)| *entry)
+        .map(|(_, name)| name)
+}
+
+fn index(lessons: &[Box<dyn Lesson>]) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    let mut modules: Vec<&str> = Vec::new();
+    for lesson in lessons {
+        let module = lesson.name().split("::").next().unwrap_or_default();
+        if !modules.contains(&module) {
+            modules.push(module);
+            index_file(module, &mut entries);
+        }
+    }
+
+    for term in &glossary::TERMS {
+        let text = format!("{} ({}): {}", term.name, term.also.join(", "), term.definition).replace(" (): ", ": ");
+        entries.push(Entry { at: term.introduced.to_string(), kind: Kind::Glossary, text });
+    }
+
+    for question in quiz::questions(None) {
+        let mut text = question.prompt.clone();
+        if let quiz::Kind::Choice { options, .. } = &question.kind {
+            text += &format!(" ({})", options.join(" / "));
+        }
+        text += &format!(" {}", question.why);
+        entries.push(Entry { at: format!("quiz {}", question.id), kind: Kind::Quiz, text });
+    }
+    entries
+}
+
+//Every comment and every line the lesson prints, under the function it's in.
+//Comments between functions go with the function after them
+fn index_file(module: &str, entries: &mut Vec<Entry>) {
+    let Some(src) = source::file(module) else { return };
+
+    //outer functions first, so a function inside another one counts as part of it
+    let mut functions: Vec<(usize, usize, String)> = Vec::new();
+    for line in src.lines() {
+        let def = line.trim_start();
+        let def = def.strip_prefix("pub ").unwrap_or(def);
+        let Some(name) = def.strip_prefix("fn ").and_then(|rest| rest.split(['(', '<']).next()) else { continue };
+        let path = format!("{module}::{name}");
+        if let Some(body) = source::function(&path) {
+            if let Some(start) = src.find(body) {
+                functions.push((start, start + body.len(), path));
+            }
+        }
+    }
+
+    let mut offset = 0;
+    for line in src.split_inclusive('\n') {
+        let at = offset;
+        offset += line.len();
+        let owner = functions
+            .iter()
+            .find(|(start, end, _)| (*start..*end).contains(&at))
+            .or_else(|| functions.iter().find(|(start, _, _)| *start > at));
+        let Some((_, _, path)) = owner else { continue };
+
+        let code = line.trim();
+        if code.contains("say!(") || code.contains("memory::draw(") {
+            if let Some(text) = literal(code).filter(|t| t.chars().any(char::is_alphabetic)) {
+                entries.push(Entry { at: path.clone(), kind: Kind::Prints, text });
+            }
+        }
+        if let Some((_, comment)) = code.split_once("//") {
+            let comment = comment.trim();
+            if comment.chars().any(char::is_alphabetic) {
+                entries.push(Entry { at: path.clone(), kind: Kind::Comment, text: comment.to_string() });
+            }
+        }
+    }
+}
+
+//The first string literal on the line, without its quotes
+fn literal(code: &str) -> Option<String> {
+    let start = code.find('"')? + 1;
+    let mut text = String::new();
+    let mut chars = code[start..].chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Some(text.trim().to_string()),
+            '\\' => match chars.next()? {
+                'n' => text.push(' '),
+                other => text.push(other),
+            },
+            c => text.push(c),
+        }
+    }
+    None
+}
@@ -1,7 +1,12 @@
 #![allow(dead_code)]
 #![allow(unused)]
+#![allow(clippy::needless_return, clippy::ptr_arg)]
 
-pub fn explain() {
+use crate::diagnostics;
+use crate::lesson::Context;
+use crate::output::say;
+
+pub fn explain(ctx: &mut Context) {
     //A slice is a reference to a contiguous series of elements in a collection. 
     //It's a type of reference (non-owning pointer)
     let mut str = String::from("barfoo foo baz bar qux fooqux barbaz");
@@ -14,8 +19,8 @@ pub fn explain() {
     //How would we keep the first word in sync?
 
     let str2 = String::from("会ったことがない|Never met 'em");
-    let slices = showcase_string_slices(&str2);
-    println!("Japanese: {}\nEnglish: {}", slices.0, slices.1);
+    let slices = showcase_string_slices(ctx, &str2);
+    say!(ctx, "Japanese: {}\nEnglish: {}", slices.0, slices.1);
 
     explain_string_literals();
 }
@@ -44,7 +49,7 @@ fn the_problem(s: &String) -> usize {
 
 
 //A reference to a part of a string
-fn showcase_string_slices(string: &String) -> (&str, &str) {
+fn showcase_string_slices<'a>(ctx: &mut Context, string: &'a String) -> (&'a str, &'a str) {
     let sep_index = string.clone()
         .find('|')
         .expect("Expected to find first '|' in input String");
@@ -74,10 +79,12 @@ fn showcase_string_slices(string: &String) -> (&str, &str) {
     let ecils_backwards: &str = gimme_a_slice(&generic_string);
     
     //So we can still do this:
-    println!("This is what we cut off: {ecils_backwards}");
+    say!(ctx, "This is what we cut off: {ecils_backwards}");
     //But we can't do this and get confused when ecils doesn't give us the right part of generic_string
     generic_string.clear();
     //println!("For the people in the back, THIS IS WHAT WE CUT OFF: {ecils_backwards}");
+    say!(ctx, "Using the slice after clearing the string doesn't compile:");
+    diagnostics::show(ctx, "slice_after_clear");
 
     return (slice, slice2)
 }
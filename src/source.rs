@@ -0,0 +1,52 @@
+//The lesson files are compiled into the binary so the source can be shown next to the lesson
+const FILES: [(&str, &str); 5] = [
+    ("ownership", include_str!("ownership.rs")),
+    ("references_and_borrowing", include_str!("references_and_borrowing.rs")),
+    ("ownership_case_study", include_str!("ownership_case_study.rs")),
+    ("slice", include_str!("slice.rs")),
+    ("vectors", include_str!("vectors.rs")),
+];
+
+pub fn file(module: &str) -> Option<&'static str> {
+    FILES.iter().find(|(name, _)| *name == module).map(|(_, src)| *src)
+}
+
+//Finds "module::function" and returns the function from its `fn` line to its closing brace
+pub fn function(path: &str) -> Option<&'static str> {
+    let (module, name) = path.split_once("::")?;
+    let src = file(module)?;
+
+    let start = find_fn(src, name)?;
+    let open = start + src[start..].find('{')?;
+
+    let mut depth = 0;
+    for (i, c) in src[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&src[start..open + i + 1]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+//Only matches real definitions: "fn name(" or "fn name<" at the start of a line (ignoring indentation and pub)
+fn find_fn(src: &str, name: &str) -> Option<usize> {
+    let mut offset = 0;
+    for line in src.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let def = trimmed.strip_prefix("pub ").unwrap_or(trimmed);
+        if let Some(rest) = def.strip_prefix("fn ") {
+            if rest.strip_prefix(name).is_some_and(|r| r.starts_with(['(', '<'])) {
+                return Some(offset + line.len() - trimmed.len());
+            }
+        }
+        offset += line.len();
+    }
+    None
+}
@@ -0,0 +1,176 @@
+mod ast;
+mod borrowck;
+mod interp;
+mod parser;
+mod programs;
+mod stepper;
+
+use crate::lesson::Context;
+use crate::memory;
+use crate::output::say;
+use borrowck::{BorrowError, Calls, Precision};
+
+//A tiny Rust-like language for trying out ownership without fighting rustc:
+//programs run with no borrow checker, and the interpreter says what goes wrong instead
+
+pub use programs::EXAMPLES;
+pub use stepper::step;
+
+//An example's name, or the source of a whole program
+pub fn source(name: &str) -> Option<&'static str> {
+    programs::example(name)
+}
+
+pub fn list(ctx: &mut Context) {
+    for (name, about, _) in EXAMPLES {
+        say!(ctx, "{name:<16} {about}");
+    }
+}
+
+//Runs a program, telling what happens to memory line by line. If it does something undefined,
+//it stops there and draws what memory looked like
+pub fn run(ctx: &mut Context, src: &str) {
+    let program = match parser::parse(src) {
+        Ok(program) => program,
+        Err(e) => {
+            say!(ctx, "line {}: {}", e.line, e.message);
+            return;
+        }
+    };
+
+    let (machine, error) = interp::run(&program);
+    for event in &machine.events {
+        say!(ctx, "  line {:<3} {}", event.line, event.text);
+    }
+    //with --dot, memory after every statement gets a file, the same ones `toy step` would draw
+    let lines: Vec<&str> = src.lines().collect();
+    for (at, step) in machine.steps.iter().enumerate() {
+        memory::export(ctx, at as u32 + 1, &stepper::caption(step, &lines), &step.memory);
+    }
+
+    match error {
+        None => say!(ctx, "\nFinished without any undefined behavior."),
+        Some(error) => {
+            say!(ctx, "\nline {}: {}", error.line, error.message);
+            if let Some(note) = rustc_note(&program, error.problem) {
+                say!(ctx, "{note}");
+            }
+            let caption = format!("memory when line {} went wrong", error.line);
+            memory::draw(ctx, error.line as u32, &caption, &machine.snapshot());
+        }
+    }
+}
+
+//Checks a program the way rustc would, without running it. Then checks it again, once telling
+//indexes apart and once looking inside the functions it calls, to show which safe programs rustc
+//only rejects because it can't see that much
+pub fn check(ctx: &mut Context, src: &str) {
+    let program = match parser::parse(src) {
+        Ok(program) => program,
+        Err(e) => {
+            say!(ctx, "line {}: {}", e.line, e.message);
+            return;
+        }
+    };
+
+    let rustc = borrowck::check(&program, Precision::Rustc, Calls::Signature);
+    let lines: Vec<&str> = src.lines().collect();
+
+    say!(ctx, "Checked like rustc (every index is the same place, a[_], but tuple fields are told apart):");
+    report(ctx, &rustc.errors, &lines);
+
+    let alternatives = [
+        ("ideal", "telling a[0] apart from a[1]", "it can't tell indexes apart", Precision::Ideal, Calls::Signature),
+        ("inlined", "looking inside the functions it calls", "it only looks at function signatures", Precision::Rustc, Calls::Inline),
+    ];
+    for (name, what, why, precision, calls) in alternatives {
+        let mut what = what.to_string();
+        let other = borrowck::check(&program, precision, calls);
+        //only the errors rustc makes because it can't see enough
+        let same = |a: &BorrowError, b: &BorrowError| a.code == b.code && a.line == b.line;
+        let extra: Vec<&BorrowError> = rustc.errors.iter().filter(|e| !other.errors.iter().any(|o| same(e, o))).collect();
+        if extra.is_empty() {
+            what[..1].make_ascii_uppercase();
+            say!(ctx, "{what} gives the same answer.");
+            continue;
+        }
+
+        say!(ctx, "rustc next to {what}:");
+        side_by_side(ctx, &lines, ("rustc", &rustc.errors), (name, &other.errors));
+        let verdict = if other.errors.is_empty() { "This program is safe, but rustc rejects it" } else { "rustc also rejects" };
+        say!(ctx, "{verdict} because {why}:");
+        for error in extra {
+            say!(ctx, "  line {}: {}", error.line, error.message);
+        }
+        say!(ctx);
+    }
+    say!(ctx);
+
+    if !rustc.lifetimes.is_empty() {
+        say!(ctx, "How long each borrow lasts (until the last use of whatever holds it, not the end of the block):");
+        for lifetime in &rustc.lifetimes {
+            let lines: Vec<String> = lifetime.lines.iter().map(|l| l.to_string()).collect();
+            let held = match lifetime.holders.is_empty() {
+                true => String::from("a temporary"),
+                false => lifetime.holders.join(", "),
+            };
+            say!(ctx, "  {:<20} held by {held}, lines {}", lifetime.borrow, lines.join(", "));
+        }
+    }
+}
+
+//   4      let y = &a[1];                 E0502    ok
+fn side_by_side(ctx: &mut Context, lines: &[&str], left: (&str, &[BorrowError]), right: (&str, &[BorrowError])) {
+    let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+    say!(ctx, "      {:width$}  {:<8} {}", "", left.0, right.0);
+    for (i, line) in lines.iter().enumerate() {
+        let verdict = |errors: &[BorrowError]| match errors.iter().find(|e| e.line == i + 1) {
+            Some(e) => e.code.to_string(),
+            None => String::new(),
+        };
+        let (l, r) = (verdict(left.1), verdict(right.1));
+        //"ok" only where the other side complains, so the difference stands out
+        let ok = |mine: String, theirs: &String| if mine.is_empty() && !theirs.is_empty() { String::from("ok") } else { mine };
+        let (l, r) = (ok(l.clone(), &r), ok(r, &l));
+        let row = format!("{:>4}  {line:width$}  {l:<8} {r}", i + 1);
+        say!(ctx, "{}", row.trim_end());
+    }
+}
+
+fn report(ctx: &mut Context, errors: &[BorrowError], lines: &[&str]) {
+    let code = |line: usize| lines.get(line.wrapping_sub(1)).map_or("", |l| l.trim());
+
+    for error in errors {
+        say!(ctx, "error[{}]: {}", error.code, error.message);
+        //the borrow, the statement that clashes with it, and the use that keeps the borrow alive, in order
+        let mut notes: Vec<(usize, String)> = error.borrowed.iter().chain(&error.later).cloned().collect();
+        notes.push((error.line, error.here.clone()));
+        notes.sort_by_key(|(line, _)| *line);
+        let mut last = None;
+        for (line, note) in notes {
+            if last != Some(line) {
+                say!(ctx, "  line {line:<3} {}", code(line));
+            }
+            last = Some(line);
+            if !note.is_empty() {
+                say!(ctx, "            {note}");
+            }
+        }
+        say!(ctx);
+    }
+
+    if errors.is_empty() {
+        say!(ctx, "No borrow errors.\n");
+    }
+}
+
+//What rustc says about the same program, from the borrow checker's first error. Undefined
+//behavior it doesn't catch (or that isn't about memory at all) gets no note
+fn rustc_note(program: &ast::Program, problem: interp::Problem) -> Option<String> {
+    if problem == interp::Problem::Other {
+        return None;
+    }
+    let checked = borrowck::check(program, Precision::Rustc, Calls::Signature);
+    let first = checked.errors.first()?;
+    Some(format!("(rustc would have refused to compile this: {} on line {}, {})", first.code, first.line, first.message))
+}
@@ -0,0 +1,125 @@
+//Types and mutability are parsed so programs read like real Rust, even where nothing looks at them
+#![allow(dead_code)]
+
+//The toy language is a small slice of Rust: enough to write add_ten, make_it_safe, unsafe_copy
+//and the borrowing examples, and nothing else (no loops, no structs, no generics)
+
+pub struct Program {
+    pub functions: Vec<Function>,
+}
+
+impl Program {
+    pub fn function(&self, name: &str) -> Option<&Function> {
+        self.functions.iter().find(|f| f.name == name)
+    }
+}
+
+pub struct Function {
+    pub name: String,
+    pub params: Vec<Param>,
+    pub returns: Option<Type>,
+    pub body: Block,
+    pub line: usize,
+}
+
+pub struct Param {
+    pub name: String,
+    pub mutable: bool,
+    pub ty: Type,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Type {
+    //i32, String...
+    Named(String),
+    //Vec<String>, Box<i32>...
+    Generic(String, Box<Type>),
+    Ref { mutable: bool, inner: Box<Type> },
+    Tuple(Vec<Type>),
+    Array(Box<Type>),
+}
+
+impl Type {
+    //Whether using a value of this type copies it instead of moving it
+    pub fn is_copy(&self) -> bool {
+        match self {
+            Type::Named(name) => name != "String",
+            Type::Generic(..) => false,
+            //&mut isn't really Copy, but passing one along reborrows it, which works out the same here
+            Type::Ref { .. } => true,
+            Type::Tuple(parts) => parts.iter().all(Type::is_copy),
+            Type::Array(inner) => inner.is_copy(),
+        }
+    }
+
+    //Whether a value of this type could be holding on to a borrow
+    pub fn has_ref(&self) -> bool {
+        match self {
+            Type::Named(_) => false,
+            Type::Ref { .. } => true,
+            Type::Generic(_, inner) | Type::Array(inner) => inner.has_ref(),
+            Type::Tuple(parts) => parts.iter().any(Type::has_ref),
+        }
+    }
+
+    pub fn unit() -> Type {
+        Type::Tuple(Vec::new())
+    }
+}
+
+pub type Block = Vec<Stmt>;
+
+pub struct Stmt {
+    pub kind: StmtKind,
+    pub line: usize,
+}
+
+pub enum StmtKind {
+    Let { name: String, mutable: bool, ty: Option<Type>, value: Expr },
+    //place = value (x += 1 is parsed as x = x + 1)
+    Assign { place: Expr, value: Expr },
+    If { cond: Expr, then: Block, otherwise: Block },
+    Return(Option<Expr>),
+    Expr(Expr),
+}
+
+#[derive(Clone, Debug)]
+pub enum Expr {
+    Int(i64),
+    Bool(bool),
+    Str(String),
+    Var(String),
+    //&place or &mut place
+    Borrow { mutable: bool, place: Box<Expr> },
+    Deref(Box<Expr>),
+    //place[index]
+    Index(Box<Expr>, Box<Expr>),
+    //place.0
+    Field(Box<Expr>, usize),
+    Binary(Box<Expr>, BinOp, Box<Expr>),
+    Neg(Box<Expr>),
+    Tuple(Vec<Expr>),
+    //[1, 2, 3]
+    Array(Vec<Expr>),
+    //vec![1, 2, 3]
+    Vec(Vec<Expr>),
+    //f(args), String::from(args), Box::new(args), drop(args)...
+    Call(String, Vec<Expr>),
+    //receiver.method(args): push, push_str, len, clone, is_positive
+    Method(Box<Expr>, String, Vec<Expr>),
+    //println!("...", args)
+    Print(String, Vec<Expr>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+}
@@ -0,0 +1,797 @@
+use super::ast::*;
+use std::collections::{BTreeSet, HashMap};
+
+//A borrow checker for the toy language that works like rustc's (non-lexical lifetimes):
+//a borrow lasts from where it's made until the last time anything holding it is used,
+//not until the end of the block. So in explain_aliasing, vec.push(4) is fine after num's last use,
+//and in explain_lifetimes, conditional_ref's borrow only exists inside the if.
+//
+//Every statement becomes a point in a control flow graph. Each point records which places it
+//touches and how (read, write, borrow, move...) and which variables it uses. Then:
+//  1. liveness: a variable is live at a point if some path from there uses it later
+//  2. each borrow is alive at the points after it where a variable holding it is live
+//  3. any point that touches a borrowed place in a way the borrow doesn't allow is an error
+
+//One step from a variable into its contents
+#[derive(Clone, Debug)]
+enum Step {
+    Field(usize),
+    //the index, when it's a plain number and the checker is precise enough to care
+    Index(Option<i64>),
+    //auto is for the derefs Rust adds by itself, like original[1] meaning (*original)[1]
+    Deref { auto: bool },
+}
+
+//A place in memory the checker can reason about, like vec[_], name.1 or *ref_1
+#[derive(Clone, Debug)]
+struct Place {
+    root: String,
+    path: Vec<Step>,
+    //goes through a reference somewhere (so it isn't owned by root)
+    behind_ref: bool,
+}
+
+impl Place {
+    fn var(name: &str) -> Place {
+        Place { root: name.to_string(), path: Vec::new(), behind_ref: false }
+    }
+
+    //Two places overlap if one contains the other. Different fields never overlap,
+    //and neither do different indexes, if they're known (with rustc's precision they never are)
+    fn overlaps(&self, other: &Place) -> bool {
+        if self.root != other.root {
+            return false;
+        }
+        for (a, b) in self.path.iter().zip(&other.path) {
+            match (a, b) {
+                (Step::Field(x), Step::Field(y)) if x != y => return false,
+                (Step::Index(Some(x)), Step::Index(Some(y))) if x != y => return false,
+                (Step::Field(_), Step::Field(_)) | (Step::Index(_), Step::Index(_)) | (Step::Deref { .. }, Step::Deref { .. }) => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    fn show(&self) -> String {
+        let mut s = shown(&self.root).to_string();
+        let mut derefed = false;
+        for step in &self.path {
+            if derefed && !matches!(step, Step::Deref { .. }) {
+                s = format!("({s})");
+            }
+            derefed = false;
+            match step {
+                Step::Field(n) => s.push_str(&format!(".{n}")),
+                Step::Index(Some(n)) => s.push_str(&format!("[{n}]")),
+                Step::Index(None) => s.push_str("[_]"),
+                Step::Deref { auto: true } => {}
+                Step::Deref { auto: false } => {
+                    s = format!("*{s}");
+                    derefed = true;
+                }
+            }
+        }
+        s
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Access {
+    //copying it out
+    Read,
+    //assigning to it
+    Write,
+    Move,
+    Borrow { mutable: bool },
+    //its variable goes out of scope
+    Drop,
+}
+
+#[derive(Default)]
+struct Point {
+    line: usize,
+    accesses: Vec<(Access, Place)>,
+    uses: BTreeSet<String>,
+    //variables that get a new value here (so they weren't live just before)
+    defs: BTreeSet<String>,
+    //variables moved away from here
+    moves: BTreeSet<String>,
+    succ: Vec<usize>,
+}
+
+struct Loan {
+    place: Place,
+    mutable: bool,
+    point: usize,
+}
+
+pub struct BorrowError {
+    pub code: &'static str,
+    pub line: usize,
+    pub message: String,
+    //what's wrong with the line itself, like "...but this needs it"
+    pub here: String,
+    //where the conflicting borrow was made
+    pub borrowed: Option<(usize, String)>,
+    //where something holding it is used afterwards (that's why the borrow is still alive)
+    pub later: Option<(usize, String)>,
+}
+
+//How long one borrow lasted, for showing that lifetimes come from liveness
+pub struct Lifetime {
+    pub borrow: String,
+    //the variable it borrows from (or through), and whether it's a &mut
+    pub root: String,
+    pub place: String,
+    pub mutable: bool,
+    pub holders: Vec<String>,
+    pub lines: Vec<usize>,
+}
+
+#[derive(Default)]
+pub struct Checked {
+    pub errors: Vec<BorrowError>,
+    pub lifetimes: Vec<Lifetime>,
+    //the variables still needed after each line. A reference that isn't in here is dead
+    pub live_after: HashMap<usize, BTreeSet<String>>,
+}
+
+//How closely places are told apart. rustc collapses every index into a[_]
+//(case6 in ownership_case_study), the ideal checker knows a[0] isn't a[1].
+//Tuple fields are told apart either way (case5)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Precision {
+    Rustc,
+    Ideal,
+}
+
+//How calls to the program's own functions are checked. rustc only looks at the signature:
+//get_first(&name) returns a reference that could point anywhere in name. Inlining looks at the body
+//and sees it's only ever &name.0 (safe_but_rejected_combine_names in ownership_case_study)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Calls {
+    Signature,
+    Inline,
+}
+
+pub fn check(program: &Program, precision: Precision, calls: Calls) -> Checked {
+    let mut checked = Checked::default();
+    for function in &program.functions {
+        let mut builder = Builder::new(program, function, precision, calls);
+        builder.build();
+        builder.finish(&mut checked);
+    }
+    checked
+}
+
+struct Builder<'p> {
+    program: &'p Program,
+    function: &'p Function,
+    precision: Precision,
+    calls: Calls,
+    points: Vec<Point>,
+    loans: Vec<Loan>,
+    //which loans each variable might be holding
+    holds: HashMap<String, BTreeSet<usize>>,
+    types: HashMap<String, Type>,
+    //variables in scope, innermost last: (name in the program, id). A shadowing let gets a new id (like x#2)
+    scope: Vec<(String, String)>,
+    declarations: HashMap<String, usize>,
+    //what each return statement hands back
+    returns: Vec<(usize, BTreeSet<usize>)>,
+    errors: Vec<BorrowError>,
+    //functions whose bodies are being looked inside right now, so a recursive one stops at its signature
+    inlining: BTreeSet<String>,
+}
+
+impl<'p> Builder<'p> {
+    fn new(program: &'p Program, function: &'p Function, precision: Precision, calls: Calls) -> Builder<'p> {
+        let types = function.params.iter().map(|p| (p.name.clone(), p.ty.clone())).collect();
+        let scope = function.params.iter().map(|p| (p.name.clone(), p.name.clone())).collect();
+        Builder {
+            program,
+            function,
+            precision,
+            calls,
+            points: Vec::new(),
+            loans: Vec::new(),
+            holds: HashMap::new(),
+            types,
+            scope,
+            declarations: HashMap::new(),
+            returns: Vec::new(),
+            errors: Vec::new(),
+            inlining: BTreeSet::new(),
+        }
+    }
+
+    fn build(&mut self) {
+        let function = self.function;
+        self.block(&function.body, Vec::new());
+    }
+
+    fn id(&self, name: &str) -> String {
+        match self.scope.iter().rev().find(|(n, _)| n == name) {
+            Some((_, id)) => id.clone(),
+            None => name.to_string(),
+        }
+    }
+
+    fn declare(&mut self, name: &str) -> String {
+        let n = self.declarations.entry(name.to_string()).or_default();
+        *n += 1;
+        let id = if *n == 1 { name.to_string() } else { format!("{name}#{n}") };
+        self.scope.push((name.to_string(), id.clone()));
+        id
+    }
+
+    fn point(&mut self, line: usize, preds: &[usize]) -> usize {
+        self.points.push(Point { line, ..Point::default() });
+        let p = self.points.len() - 1;
+        for &pred in preds {
+            self.points[pred].succ.push(p);
+        }
+        p
+    }
+
+    //Adds a block's statements after preds, and returns the points control leaves the block from
+    fn block(&mut self, stmts: &[Stmt], mut preds: Vec<usize>) -> Vec<usize> {
+        let mut declared = Vec::new();
+        let outer = self.scope.len();
+        for stmt in stmts {
+            preds = self.stmt(stmt, preds, &mut declared);
+        }
+
+        //variables go out of scope at the end of their block
+        if !declared.is_empty() && !preds.is_empty() {
+            let line = stmts.last().map(|s| s.line).unwrap_or(0);
+            let p = self.point(line, &preds);
+            for name in declared {
+                self.points[p].accesses.push((Access::Drop, Place::var(&name)));
+            }
+            preds = vec![p];
+        }
+        self.scope.truncate(outer);
+        preds
+    }
+
+    fn stmt(&mut self, stmt: &Stmt, preds: Vec<usize>, declared: &mut Vec<String>) -> Vec<usize> {
+        //a statement after a return can't be reached
+        if preds.is_empty() && !self.points.is_empty() {
+            return preds;
+        }
+        let p = self.point(stmt.line, &preds);
+        match &stmt.kind {
+            StmtKind::Let { name, ty, value, .. } => {
+                let loans = self.value(p, value);
+                let ty = ty.clone().or_else(|| self.type_of(value));
+                let id = self.declare(name);
+                if let Some(ty) = ty {
+                    self.types.insert(id.clone(), ty);
+                }
+                self.points[p].defs.insert(id.clone());
+                self.holds.entry(id.clone()).or_default().extend(loans);
+                declared.push(id);
+            }
+            StmtKind::Assign { place, value } => {
+                let loans = self.value(p, value);
+                if let Expr::Var(name) = place {
+                    let id = self.id(name);
+                    self.points[p].defs.insert(id.clone());
+                    self.holds.entry(id.clone()).or_default().extend(loans);
+                    self.points[p].accesses.push((Access::Write, Place::var(&id)));
+                } else {
+                    let place = self.place(p, place);
+                    self.points[p].accesses.push((Access::Write, place));
+                }
+            }
+            StmtKind::If { cond, then, otherwise } => {
+                self.value(p, cond);
+                let mut exits = self.block(then, vec![p]);
+                if otherwise.is_empty() {
+                    exits.push(p);
+                } else {
+                    exits.extend(self.block(otherwise, vec![p]));
+                }
+                return exits;
+            }
+            StmtKind::Return(value) => {
+                let loans = match value {
+                    Some(e) => self.value(p, e),
+                    None => BTreeSet::new(),
+                };
+                self.returns.push((p, loans));
+                return Vec::new();
+            }
+            StmtKind::Expr(e) => {
+                self.value(p, e);
+            }
+        }
+        vec![p]
+    }
+
+    fn is_place(expr: &Expr) -> bool {
+        matches!(expr, Expr::Var(_) | Expr::Field(..) | Expr::Index(..) | Expr::Deref(_))
+    }
+
+    //Records what evaluating expr does at point p, and returns the loans the resulting value holds
+    fn value(&mut self, p: usize, expr: &Expr) -> BTreeSet<usize> {
+        match expr {
+            Expr::Int(_) | Expr::Bool(_) | Expr::Str(_) => BTreeSet::new(),
+            Expr::Var(_) | Expr::Field(..) | Expr::Index(..) | Expr::Deref(_) => {
+                let ty = self.type_of(expr);
+                let place = self.place(p, expr);
+                let copy = ty.as_ref().is_none_or(Type::is_copy);
+                if copy {
+                    self.points[p].accesses.push((Access::Read, place.clone()));
+                } else {
+                    self.moved(p, &place);
+                }
+                //copying a reference (or moving something holding one) takes its loans along
+                match ty {
+                    Some(ty) if !ty.has_ref() => BTreeSet::new(),
+                    _ => self.holds.get(&place.root).cloned().unwrap_or_default(),
+                }
+            }
+            Expr::Borrow { mutable, place } => {
+                let place = self.place(p, place);
+                self.points[p].accesses.push((Access::Borrow { mutable: *mutable }, place.clone()));
+                //a reborrow through a reference (like &*ref_1) also keeps that reference's loans alive
+                let mut loans = match place.behind_ref {
+                    true => self.holds.get(&place.root).cloned().unwrap_or_default(),
+                    false => BTreeSet::new(),
+                };
+                self.loans.push(Loan { place, mutable: *mutable, point: p });
+                loans.insert(self.loans.len() - 1);
+                loans
+            }
+            Expr::Binary(left, _, right) => {
+                self.value(p, left);
+                self.value(p, right);
+                BTreeSet::new()
+            }
+            Expr::Neg(inner) => {
+                self.value(p, inner);
+                BTreeSet::new()
+            }
+            Expr::Tuple(parts) | Expr::Array(parts) | Expr::Vec(parts) => {
+                parts.iter().flat_map(|e| self.value(p, e)).collect()
+            }
+            Expr::Call(name, args) => {
+                let loans: BTreeSet<usize> = args.iter().flat_map(|e| self.value(p, e)).collect();
+                match name.as_str() {
+                    "drop" => BTreeSet::new(),
+                    "String::from" | "String::new" | "Box::new" => loans,
+                    _ => {
+                        let function = self.program.function(name);
+                        if self.calls == Calls::Inline && !self.inlining.contains(name) {
+                            if let Some(body) = function.and_then(|f| inline(f, args)) {
+                                self.inlining.insert(name.clone());
+                                let loans = self.value(p, &body);
+                                self.inlining.remove(name);
+                                return loans;
+                            }
+                        }
+                        //only the signature counts: if it returns a reference, it could be any of the ones passed in
+                        match function.and_then(|f| f.returns.as_ref()) {
+                            Some(ty) if ty.has_ref() => loans,
+                            _ => BTreeSet::new(),
+                        }
+                    }
+                }
+            }
+            Expr::Method(receiver, method, args) => {
+                for arg in args {
+                    self.value(p, arg);
+                }
+                //methods borrow their receiver: push and push_str mutably, the rest immutably
+                let mutable = method == "push" || method == "push_str";
+                if Self::is_place(receiver) {
+                    let place = self.place(p, receiver);
+                    self.points[p].accesses.push((Access::Borrow { mutable }, place));
+                } else {
+                    self.value(p, receiver);
+                }
+                BTreeSet::new()
+            }
+            Expr::Print(format, args) => {
+                //println! only ever borrows what it prints
+                for arg in args {
+                    if Self::is_place(arg) {
+                        let place = self.place(p, arg);
+                        self.points[p].accesses.push((Access::Borrow { mutable: false }, place));
+                    } else {
+                        self.value(p, arg);
+                    }
+                }
+                for name in inline_args(format) {
+                    let id = self.id(name);
+                    self.points[p].accesses.push((Access::Borrow { mutable: false }, Place::var(&id)));
+                    self.points[p].uses.insert(id);
+                }
+                BTreeSet::new()
+            }
+        }
+    }
+
+    fn moved(&mut self, p: usize, place: &Place) {
+        if place.behind_ref || place.path.iter().any(|s| matches!(s, Step::Index(_))) {
+            let why = if place.behind_ref { "which is behind a reference" } else { "because it's inside a collection" };
+            self.errors.push(BorrowError {
+                code: "E0507",
+                line: self.points[p].line,
+                message: format!("cannot move out of {}, {why}", place.show()),
+                here: String::new(),
+                borrowed: None,
+                later: None,
+            });
+        }
+        if place.path.is_empty() {
+            self.points[p].moves.insert(place.root.clone());
+        }
+        self.points[p].accesses.push((Access::Move, place.clone()));
+    }
+
+    //Turns a place expression into a Place, adding the derefs Rust adds by itself
+    fn place(&mut self, p: usize, expr: &Expr) -> Place {
+        match expr {
+            Expr::Var(name) => {
+                let id = self.id(name);
+                self.points[p].uses.insert(id.clone());
+                Place::var(&id)
+            }
+            Expr::Field(base, n) => {
+                let mut place = self.auto_deref(p, base, false);
+                place.path.push(Step::Field(*n));
+                place
+            }
+            Expr::Index(base, index) => {
+                self.value(p, index);
+                let mut place = self.auto_deref(p, base, true);
+                let n = match (self.precision, index.as_ref()) {
+                    (Precision::Ideal, Expr::Int(n)) => Some(*n),
+                    _ => None,
+                };
+                place.path.push(Step::Index(n));
+                place
+            }
+            //*&x is just x (this is what inlining get_first(&name) leaves behind)
+            Expr::Deref(inner) if matches!(inner.as_ref(), Expr::Borrow { .. }) => {
+                let Expr::Borrow { place, .. } = inner.as_ref() else { unreachable!() };
+                self.place(p, place)
+            }
+            Expr::Deref(inner) => {
+                let ty = self.type_of(inner);
+                let mut place = match Self::is_place(inner) {
+                    true => self.place(p, inner),
+                    //like *get_first(&name): the value only lives for this statement
+                    false => {
+                        self.value(p, inner);
+                        Place::var("(temporary)")
+                    }
+                };
+                if matches!(ty, Some(Type::Ref { .. })) {
+                    place.behind_ref = true;
+                }
+                place.path.push(Step::Deref { auto: false });
+                place
+            }
+            other => {
+                self.value(p, other);
+                Place::var("(temporary)")
+            }
+        }
+    }
+
+    //base.0 and base[i] go through references and Boxes first. Vecs don't need it, indexing one is its own thing
+    fn auto_deref(&mut self, p: usize, base: &Expr, boxes_too: bool) -> Place {
+        //(&name).0 is name.0
+        let base = match base {
+            Expr::Borrow { place, .. } => place.as_ref(),
+            other => other,
+        };
+        let mut ty = self.type_of(base);
+        let mut place = self.place(p, base);
+        loop {
+            ty = match ty {
+                Some(Type::Ref { inner, .. }) => {
+                    place.behind_ref = true;
+                    Some(*inner)
+                }
+                Some(Type::Generic(name, inner)) if boxes_too && name == "Box" => Some(*inner),
+                _ => return place,
+            };
+            place.path.push(Step::Deref { auto: true });
+        }
+    }
+
+    //As much of an expression's type as can be worked out (None when it can't)
+    fn type_of(&self, expr: &Expr) -> Option<Type> {
+        let named = |name: &str| Some(Type::Named(name.to_string()));
+        match expr {
+            Expr::Int(_) | Expr::Neg(_) => named("i32"),
+            Expr::Bool(_) => named("bool"),
+            Expr::Str(_) => Some(Type::Ref { mutable: false, inner: Box::new(Type::Named(String::from("str"))) }),
+            Expr::Var(name) => self.types.get(&self.id(name)).cloned(),
+            Expr::Borrow { mutable, place } => {
+                Some(Type::Ref { mutable: *mutable, inner: Box::new(self.type_of(place)?) })
+            }
+            Expr::Deref(inner) => match self.type_of(inner)? {
+                Type::Ref { inner, .. } => Some(*inner),
+                Type::Generic(name, inner) if name == "Box" => Some(*inner),
+                _ => None,
+            },
+            Expr::Field(base, n) => match strip(self.type_of(base)?, false) {
+                Type::Tuple(parts) => parts.get(*n).cloned(),
+                _ => None,
+            },
+            Expr::Index(base, _) => match strip(self.type_of(base)?, true) {
+                Type::Array(inner) => Some(*inner),
+                Type::Generic(name, inner) if name == "Vec" => Some(*inner),
+                _ => None,
+            },
+            Expr::Binary(_, op, _) => match op {
+                BinOp::Add | BinOp::Sub | BinOp::Mul => named("i32"),
+                _ => named("bool"),
+            },
+            Expr::Tuple(parts) => Some(Type::Tuple(parts.iter().map(|e| self.type_of(e)).collect::<Option<_>>()?)),
+            Expr::Array(parts) => Some(Type::Array(Box::new(self.type_of(parts.first()?)?))),
+            Expr::Vec(parts) => {
+                let inner = parts.first().and_then(|e| self.type_of(e)).unwrap_or(Type::Named(String::from("i32")));
+                Some(Type::Generic(String::from("Vec"), Box::new(inner)))
+            }
+            Expr::Call(name, args) => match name.as_str() {
+                "String::from" | "String::new" => named("String"),
+                "Box::new" => Some(Type::Generic(String::from("Box"), Box::new(self.type_of(args.first()?)?))),
+                "drop" => Some(Type::unit()),
+                _ => Some(self.program.function(name)?.returns.clone().unwrap_or_else(Type::unit)),
+            },
+            Expr::Method(receiver, method, _) => match method.as_str() {
+                "clone" => Some(strip(self.type_of(receiver)?, false)),
+                "len" => named("usize"),
+                "is_positive" => named("bool"),
+                "abs" => named("i32"),
+                _ => Some(Type::unit()),
+            },
+            Expr::Print(..) => Some(Type::unit()),
+        }
+    }
+
+    fn finish(mut self, checked: &mut Checked) {
+        let live = self.liveness();
+        for point in &self.points {
+            let after = checked.live_after.entry(point.line).or_default();
+            for &s in &point.succ {
+                after.extend(live[s].iter().map(|v| shown(v).to_string()));
+            }
+        }
+        self.check_moves();
+        self.check_loans(&live, checked);
+        self.check_returns();
+        checked.errors.append(&mut self.errors);
+    }
+
+    //live[p] = the variables live on the way into point p
+    fn liveness(&self) -> Vec<BTreeSet<String>> {
+        let mut live = vec![BTreeSet::new(); self.points.len()];
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for p in (0..self.points.len()).rev() {
+                let point = &self.points[p];
+                let mut set: BTreeSet<String> = point.succ.iter().flat_map(|&s| live[s].iter().cloned()).collect();
+                set.retain(|v| !point.defs.contains(v));
+                set.extend(point.uses.iter().cloned());
+                if set != live[p] {
+                    live[p] = set;
+                    changed = true;
+                }
+            }
+        }
+        live
+    }
+
+    //Using a variable after it's moved (on some path to here) is E0382
+    fn check_moves(&mut self) {
+        let mut moved_in: Vec<HashMap<String, usize>> = vec![HashMap::new(); self.points.len()];
+        //points only ever link forward, so one pass in order is enough
+        for p in 0..self.points.len() {
+            let point = &self.points[p];
+            for name in &point.uses {
+                if let Some(&line) = moved_in[p].get(name) {
+                    self.errors.push(BorrowError {
+                        code: "E0382",
+                        line: point.line,
+                        message: format!("use of moved value: {}", shown(name)),
+                        here: String::from("...but this uses it"),
+                        borrowed: None,
+                        later: Some((line, format!("{} was moved here", shown(name)))),
+                    });
+                }
+            }
+            let mut out = moved_in[p].clone();
+            out.retain(|name, _| !point.defs.contains(name));
+            for name in &point.moves {
+                out.insert(name.clone(), point.line);
+            }
+            for &s in &point.succ {
+                for (name, &line) in &out {
+                    moved_in[s].entry(name.clone()).or_insert(line);
+                }
+            }
+        }
+    }
+
+    fn check_loans(&mut self, live: &[BTreeSet<String>], checked: &mut Checked) {
+        for (id, loan) in self.loans.iter().enumerate() {
+            let mut holders: Vec<String> = self.holds.iter().filter(|(_, l)| l.contains(&id)).map(|(v, _)| v.clone()).collect();
+            holders.sort();
+            let alive = |p: usize| live[p].iter().any(|v| holders.contains(v));
+
+            //walk forward from the borrow for as long as something holding it is live
+            let mut lines = BTreeSet::from([self.points[loan.point].line]);
+            let mut seen = vec![false; self.points.len()];
+            let mut todo = self.points[loan.point].succ.clone();
+            while let Some(p) = todo.pop() {
+                if seen[p] || !alive(p) {
+                    continue;
+                }
+                seen[p] = true;
+                lines.insert(self.points[p].line);
+                todo.extend(&self.points[p].succ);
+
+                for (access, place) in &self.points[p].accesses {
+                    if let Some((code, message, here)) = conflict(*access, place, loan) {
+                        let later = self.later_use(p, &holders);
+                        let holder = holders.first().map(|h| format!(" (by {})", shown(h))).unwrap_or_default();
+                        let kind = if loan.mutable { "mutably" } else { "immutably" };
+                        self.errors.push(BorrowError {
+                            code,
+                            line: self.points[p].line,
+                            message,
+                            here,
+                            borrowed: Some((self.points[loan.point].line, format!("{} is borrowed {kind} here{holder}", loan.place.show()))),
+                            later,
+                        });
+                    }
+                }
+            }
+
+            let amp = if loan.mutable { "&mut " } else { "&" };
+            checked.lifetimes.push(Lifetime {
+                borrow: format!("{amp}{}", loan.place.show()),
+                root: shown(&loan.place.root).to_string(),
+                place: loan.place.show(),
+                mutable: loan.mutable,
+                holders: holders.iter().map(|h| shown(h).to_string()).collect(),
+                lines: lines.into_iter().collect(),
+            });
+        }
+    }
+
+    //The first point at or after p that uses one of the holders
+    fn later_use(&self, p: usize, holders: &[String]) -> Option<(usize, String)> {
+        let mut seen = vec![false; self.points.len()];
+        let mut todo = vec![p];
+        while !todo.is_empty() {
+            todo.sort_unstable_by(|a, b| b.cmp(a));
+            let q = todo.pop()?;
+            if seen[q] {
+                continue;
+            }
+            seen[q] = true;
+            if let Some(h) = holders.iter().find(|h| self.points[q].uses.contains(*h)) {
+                return Some((self.points[q].line, format!("{} is used later here", shown(h))));
+            }
+            todo.extend(&self.points[q].succ);
+        }
+        None
+    }
+
+    //Returning a borrow of something owned by this function leaves it dangling
+    fn check_returns(&mut self) {
+        for (p, loans) in &self.returns {
+            for &id in loans {
+                let loan = &self.loans[id];
+                if !loan.place.behind_ref {
+                    self.errors.push(BorrowError {
+                        code: "E0515",
+                        line: self.points[*p].line,
+                        message: format!("cannot return a reference to {}, it's dropped when {} returns", shown(&loan.place.root), self.function.name),
+                        here: String::from("...and returned here"),
+                        borrowed: Some((self.points[loan.point].line, format!("{} is borrowed here", loan.place.show()))),
+                        later: None,
+                    });
+                }
+            }
+        }
+    }
+}
+
+//Whether touching place like this is allowed while loan is alive, and the error if it isn't
+fn conflict(access: Access, place: &Place, loan: &Loan) -> Option<(&'static str, String, String)> {
+    if !place.overlaps(&loan.place) {
+        return None;
+    }
+    let p = place.show();
+    let needs = || String::from("...but this needs it");
+    match (access, loan.mutable) {
+        (Access::Read, false) | (Access::Borrow { mutable: false }, false) => None,
+        (Access::Read, true) => Some(("E0503", format!("cannot use {p} because it was mutably borrowed"), needs())),
+        (Access::Borrow { mutable: false }, true) => {
+            Some(("E0502", format!("cannot borrow {p} as immutable because it is also borrowed as mutable"), needs()))
+        }
+        (Access::Borrow { mutable: true }, false) => {
+            Some(("E0502", format!("cannot borrow {p} as mutable because it is also borrowed as immutable"), needs()))
+        }
+        (Access::Borrow { mutable: true }, true) => {
+            Some(("E0499", format!("cannot borrow {p} as mutable more than once at a time"), needs()))
+        }
+        (Access::Write, _) => Some(("E0506", format!("cannot assign to {p} because it is borrowed"), needs())),
+        (Access::Move, _) => Some(("E0505", format!("cannot move out of {p} because it is borrowed"), needs())),
+        //a reference going out of scope doesn't hurt what it points to
+        (Access::Drop, _) if loan.place.behind_ref => None,
+        (Access::Drop, _) => {
+            Some(("E0597", format!("{p} does not live long enough"), format!("...but {p} goes away at the end of this block")))
+        }
+    }
+}
+
+//A call to a function whose whole body is one expression, with the arguments put in for the parameters
+fn inline(function: &Function, args: &[Expr]) -> Option<Expr> {
+    let [Stmt { kind: StmtKind::Return(Some(body)), .. }] = function.body.as_slice() else {
+        return None;
+    };
+    let params: HashMap<&str, &Expr> = function.params.iter().map(|p| p.name.as_str()).zip(args).collect();
+    Some(substitute(body, &params))
+}
+
+fn substitute(expr: &Expr, params: &HashMap<&str, &Expr>) -> Expr {
+    let sub = |e: &Expr| Box::new(substitute(e, params));
+    let all = |es: &[Expr]| es.iter().map(|e| substitute(e, params)).collect();
+    match expr {
+        Expr::Var(name) => params.get(name.as_str()).map_or_else(|| expr.clone(), |&arg| arg.clone()),
+        Expr::Borrow { mutable, place } => Expr::Borrow { mutable: *mutable, place: sub(place) },
+        Expr::Deref(inner) => Expr::Deref(sub(inner)),
+        Expr::Index(base, index) => Expr::Index(sub(base), sub(index)),
+        Expr::Field(base, n) => Expr::Field(sub(base), *n),
+        Expr::Binary(left, op, right) => Expr::Binary(sub(left), *op, sub(right)),
+        Expr::Neg(inner) => Expr::Neg(sub(inner)),
+        Expr::Tuple(parts) => Expr::Tuple(all(parts)),
+        Expr::Array(parts) => Expr::Array(all(parts)),
+        Expr::Vec(parts) => Expr::Vec(all(parts)),
+        Expr::Call(name, args) => Expr::Call(name.clone(), all(args)),
+        Expr::Method(receiver, name, args) => Expr::Method(sub(receiver), name.clone(), all(args)),
+        Expr::Int(_) | Expr::Bool(_) | Expr::Str(_) | Expr::Print(..) => expr.clone(),
+    }
+}
+
+//References and Boxes in the way of a field or index
+fn strip(ty: Type, boxes_too: bool) -> Type {
+    match ty {
+        Type::Ref { inner, .. } => strip(*inner, boxes_too),
+        Type::Generic(name, inner) if boxes_too && name == "Box" => strip(*inner, boxes_too),
+        other => other,
+    }
+}
+
+//The {name}s in a println! format string
+fn inline_args(format: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = format;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}') else { break };
+        let name = rest[open + 1..open + close].split(':').next().unwrap_or("");
+        if !name.is_empty() {
+            names.push(name);
+        }
+        rest = &rest[open + close + 1..];
+    }
+    names
+}
+
+//x#2 (the second x declared) is still just x to whoever wrote the program
+fn shown(id: &str) -> &str {
+    id.split('#').next().unwrap_or(id)
+}
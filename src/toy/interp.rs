@@ -0,0 +1,960 @@
+use super::ast::*;
+use crate::memory::{self, Memory};
+
+//Runs toy programs the way a C compiler would: no borrow checker, no safety net.
+//Instead, it keeps track of every heap cell, who owns it and when it's freed,
+//so when the program does something undefined (use after move, use after free, double free)
+//it stops and says exactly what happened
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Unit,
+    Int(i64),
+    Bool(bool),
+    //a string literal, which isn't on the heap at all
+    Text(String),
+    //owns a heap cell: a String, a Vec or a Box
+    Heap(usize),
+    Ref { to: Place, mutable: bool },
+    Tuple(Vec<Value>),
+    Array(Vec<Value>),
+    //what's left in a variable after it's moved out of (and the line it moved on)
+    Moved(usize),
+}
+
+impl Value {
+    fn is_copy(&self) -> bool {
+        match self {
+            Value::Heap(_) | Value::Moved(_) => false,
+            Value::Tuple(parts) | Value::Array(parts) => parts.iter().all(Value::is_copy),
+            _ => true,
+        }
+    }
+
+    fn moved_at(&self) -> Option<usize> {
+        match self {
+            Value::Moved(line) => Some(*line),
+            Value::Tuple(parts) | Value::Array(parts) => parts.iter().find_map(Value::moved_at),
+            _ => None,
+        }
+    }
+}
+
+//Where a value lives: a variable in some stack frame, or a heap cell. The path goes into
+//tuple fields, array/Vec elements and Box contents from there
+#[derive(Clone, Debug, PartialEq)]
+pub struct Place {
+    pub root: Root,
+    pub path: Vec<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Root {
+    //frames are numbered when they're created, so a reference into a frame that returned can be noticed
+    Local { frame: usize, slot: usize },
+    Heap(usize),
+}
+
+pub enum Object {
+    Str { text: String, cap: usize },
+    Vec { items: Vec<Value>, cap: usize },
+    Box(Value),
+}
+
+pub struct Cell {
+    pub object: Object,
+    pub freed_at: Option<usize>,
+}
+
+pub struct Slot {
+    pub name: String,
+    pub mutable: bool,
+    pub value: Value,
+    //went out of scope (its block ended)
+    pub dead: bool,
+}
+
+pub struct Frame {
+    pub id: usize,
+    pub function: String,
+    pub slots: Vec<Slot>,
+    //where each open block's variables start, so they can be dropped when it ends
+    scopes: Vec<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Problem {
+    UseAfterMove,
+    UseAfterFree,
+    DoubleFree,
+    //a reference to a variable whose function already returned
+    Dangling,
+    //everything else: type mix-ups, out of bounds, calling a function that doesn't exist...
+    Other,
+}
+
+pub struct RuntimeError {
+    pub line: usize,
+    pub problem: Problem,
+    pub message: String,
+}
+
+//Something worth telling the student about, like "allocated #0" or "dropped #1"
+pub struct Event {
+    pub line: usize,
+    pub text: String,
+}
+
+type Run<T> = Result<T, RuntimeError>;
+
+enum Flow {
+    Next,
+    Return(Value),
+}
+
+//The state after one statement, for stepping through a program
+pub struct Step {
+    pub line: usize,
+    //when it's not just the line running, like "end of main"
+    pub note: Option<String>,
+    pub memory: Memory,
+    pub locals: Vec<Local>,
+    //how many events had happened by then
+    pub events: usize,
+}
+
+//A variable in the function that's running
+pub struct Local {
+    pub name: String,
+    pub mutable: bool,
+    pub moved: bool,
+    //Some(mutable) if it's a reference
+    pub reference: Option<bool>,
+    //what it points to, and what that points to...: "a --> #0 Box(2) --> 2"
+    pub chain: String,
+}
+
+pub struct Machine<'p> {
+    program: &'p Program,
+    pub heap: Vec<Cell>,
+    pub frames: Vec<Frame>,
+    pub events: Vec<Event>,
+    pub steps: Vec<Step>,
+    next_frame: usize,
+    line: usize,
+}
+
+//How many calls deep a program can go before it counts as runaway recursion
+const MAX_DEPTH: usize = 100;
+
+//Runs main and returns everything that happened, plus the error that stopped it (if any)
+pub fn run(program: &Program) -> (Machine<'_>, Option<RuntimeError>) {
+    let mut machine = Machine::new(program);
+    let error = machine.call("main", Vec::new()).err();
+    (machine, error)
+}
+
+impl<'p> Machine<'p> {
+    pub fn new(program: &'p Program) -> Machine<'p> {
+        Machine { program, heap: Vec::new(), frames: Vec::new(), events: Vec::new(), steps: Vec::new(), next_frame: 0, line: 0 }
+    }
+
+    fn fail<T>(&self, problem: Problem, message: impl Into<String>) -> Run<T> {
+        Err(RuntimeError { line: self.line, problem, message: message.into() })
+    }
+
+    fn event(&mut self, text: String) {
+        self.events.push(Event { line: self.line, text });
+    }
+
+    fn frame(&mut self) -> &mut Frame {
+        self.frames.last_mut().expect("there's always a frame while running")
+    }
+
+    fn alloc(&mut self, object: Object) -> usize {
+        self.heap.push(Cell { object, freed_at: None });
+        let address = self.heap.len() - 1;
+        let text = self.show_cell(address);
+        self.event(format!("allocated #{address} {text}"));
+        address
+    }
+
+    //Calls a function: arguments are already evaluated (and moved) by the caller
+    pub fn call(&mut self, name: &str, args: Vec<Value>) -> Run<Value> {
+        let program = self.program;
+        let Some(function) = program.function(name) else {
+            return self.fail(Problem::Other, format!("there's no function called {name}"));
+        };
+        if function.params.len() != args.len() {
+            return self.fail(Problem::Other, format!("{name} takes {} arguments, not {}", function.params.len(), args.len()));
+        }
+        if self.frames.len() >= MAX_DEPTH {
+            return self.fail(Problem::Other, format!("calling {name} goes more than {MAX_DEPTH} calls deep, so it probably never stops (a real program would overflow its stack)"));
+        }
+
+        let id = self.next_frame;
+        self.next_frame += 1;
+        let slots = function
+            .params
+            .iter()
+            .zip(args)
+            .map(|(param, value)| Slot { name: param.name.clone(), mutable: param.mutable, value, dead: false })
+            .collect();
+        self.frames.push(Frame { id, function: name.to_string(), slots, scopes: vec![0] });
+
+        let returned = match self.block(&function.body)? {
+            Flow::Return(value) => value,
+            Flow::Next => Value::Unit,
+        };
+        self.close_scope()?;
+        self.record(Some(format!("end of {name}: its variables are dropped")));
+        self.frames.pop();
+        Ok(returned)
+    }
+
+    fn block(&mut self, stmts: &[Stmt]) -> Run<Flow> {
+        for stmt in stmts {
+            if let Flow::Return(value) = self.stmt(stmt)? {
+                return Ok(Flow::Return(value));
+            }
+        }
+        Ok(Flow::Next)
+    }
+
+    //Drops everything declared in the innermost block, newest first, like Rust does
+    fn close_scope(&mut self) -> Run<()> {
+        let start = self.frame().scopes.pop().unwrap_or(0);
+        for i in (start..self.frame().slots.len()).rev() {
+            let slot = &mut self.frame().slots[i];
+            if slot.dead {
+                continue;
+            }
+            slot.dead = true;
+            let value = std::mem::replace(&mut slot.value, Value::Unit);
+            let owner = slot.name.clone();
+            self.drop_value(value, &owner)?;
+        }
+        Ok(())
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) -> Run<Flow> {
+        self.line = stmt.line;
+        match &stmt.kind {
+            StmtKind::Let { name, mutable, value, .. } => {
+                let value = self.eval(value)?;
+                if let Value::Heap(address) = value {
+                    self.event(format!("{name} owns #{address}"));
+                }
+                self.frame().slots.push(Slot { name: name.clone(), mutable: *mutable, value, dead: false });
+            }
+            StmtKind::Assign { place, value } => {
+                let value = self.eval(value)?;
+                let (place, _) = self.place(place)?;
+                let old = std::mem::replace(self.get_mut(&place)?, value);
+                //the old value gets dropped, unless it was already moved out
+                if old.moved_at().is_none() {
+                    let name = self.name_of(&place);
+                    self.drop_value(old, &name)?;
+                }
+            }
+            StmtKind::If { cond, then, otherwise } => {
+                let taken = match self.eval(cond)? {
+                    Value::Bool(b) => b,
+                    Value::Int(n) => n != 0,
+                    other => return self.fail(Problem::Other, format!("if needs a bool, not {}", self.show(&other))),
+                };
+                self.record(None);
+                let branch = if taken { then } else { otherwise };
+                let start = self.frame().slots.len();
+                self.frame().scopes.push(start);
+                let flow = self.block(branch)?;
+                let declared = self.frame().slots.len() > start;
+                self.close_scope()?;
+                if declared {
+                    self.record(Some(String::from("end of the block: its variables are dropped")));
+                }
+                return Ok(flow);
+            }
+            StmtKind::Return(value) => {
+                let value = match value {
+                    Some(e) => self.eval(e)?,
+                    None => Value::Unit,
+                };
+                self.record(None);
+                return Ok(Flow::Return(value));
+            }
+            StmtKind::Expr(e) => {
+                //temporaries (like an unused return value) die at the end of the statement
+                let value = self.eval(e)?;
+                self.drop_value(value, "a temporary")?;
+            }
+        }
+        self.record(None);
+        Ok(Flow::Next)
+    }
+
+    //Frees whatever the value owns. Freeing something that's already free is a double free
+    fn drop_value(&mut self, value: Value, owner: &str) -> Run<()> {
+        match value {
+            Value::Heap(address) => {
+                if let Some(freed) = self.heap[address].freed_at {
+                    return self.fail(
+                        Problem::DoubleFree,
+                        format!("double free: {owner} frees #{address}, but it was already freed on line {freed}"),
+                    );
+                }
+                let text = self.show_cell(address);
+                self.heap[address].freed_at = Some(self.line);
+                self.event(format!("dropped {owner}, freeing #{address} {text}"));
+
+                //whatever it owns goes with it (the freed cell keeps its contents so it can still be drawn)
+                let inner = match &self.heap[address].object {
+                    Object::Vec { items, .. } => items.clone(),
+                    Object::Box(value) => vec![value.clone()],
+                    Object::Str { .. } => Vec::new(),
+                };
+                for item in inner {
+                    self.drop_value(item, &format!("#{address}"))?;
+                }
+                Ok(())
+            }
+            Value::Tuple(parts) | Value::Array(parts) => {
+                for part in parts {
+                    self.drop_value(part, owner)?;
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    //Finds the place an expression names. The bool says whether the place was reached through
+    //a reference (moving out of those is what unsafe_copy tries to do)
+    fn place(&mut self, expr: &Expr) -> Run<(Place, bool)> {
+        match expr {
+            Expr::Var(name) => {
+                let frame = self.frames.last().expect("there's always a frame while running");
+                match frame.slots.iter().rposition(|s| s.name == *name && !s.dead) {
+                    Some(slot) => Ok((Place { root: Root::Local { frame: frame.id, slot }, path: Vec::new() }, false)),
+                    None => self.fail(Problem::Other, format!("there's no variable called {name} here")),
+                }
+            }
+            Expr::Field(base, n) => {
+                let (mut place, through_ref) = self.auto_deref(base)?;
+                match self.get(&place)? {
+                    Value::Tuple(parts) if *n < parts.len() => {
+                        place.path.push(*n);
+                        Ok((place, through_ref))
+                    }
+                    other => self.fail(Problem::Other, format!("{} doesn't have a field {n}", self.show(&other))),
+                }
+            }
+            Expr::Index(base, index) => {
+                let i = match self.eval(index)? {
+                    Value::Int(i) if i >= 0 => i as usize,
+                    other => return self.fail(Problem::Other, format!("can't index with {}", self.show(&other))),
+                };
+                let (mut place, through_ref) = self.auto_deref(base)?;
+                //Vecs and Boxes keep their elements on the heap
+                if let Value::Heap(address) = self.get(&place)? {
+                    place = Place { root: Root::Heap(address), path: Vec::new() };
+                }
+                let len = match &place.root {
+                    Root::Heap(address) if place.path.is_empty() => match self.cell(*address)? {
+                        Object::Vec { items, .. } => items.len(),
+                        Object::Box(Value::Array(items)) => items.len(),
+                        _ => return self.fail(Problem::Other, "only arrays and Vecs can be indexed"),
+                    },
+                    _ => match self.get(&place)? {
+                        Value::Array(items) => items.len(),
+                        _ => return self.fail(Problem::Other, "only arrays and Vecs can be indexed"),
+                    },
+                };
+                if i >= len {
+                    return self.fail(Problem::Other, format!("index {i} is out of bounds (the length is {len})"));
+                }
+                place.path.push(i);
+                Ok((place, through_ref))
+            }
+            Expr::Deref(inner) => {
+                let target = match inner.as_ref() {
+                    Expr::Var(_) | Expr::Field(..) | Expr::Index(..) | Expr::Deref(_) => {
+                        let (place, _) = self.place(inner)?;
+                        self.get(&place)?
+                    }
+                    other => self.eval(other)?,
+                };
+                match target {
+                    Value::Ref { to, .. } => Ok((to, true)),
+                    Value::Heap(address) => Ok((Place { root: Root::Heap(address), path: Vec::new() }, false)),
+                    other => self.fail(Problem::Other, format!("can't dereference {}", self.show(&other))),
+                }
+            }
+            _ => self.fail(Problem::Other, "that's a value, not a place in memory"),
+        }
+    }
+
+    //Like place(), but follows references first, the way name.0 and v[0] do when name or v is a reference
+    fn auto_deref(&mut self, expr: &Expr) -> Run<(Place, bool)> {
+        let (mut place, mut through_ref) = self.place(expr)?;
+        while let Value::Ref { to, .. } = self.get(&place)? {
+            place = to;
+            through_ref = true;
+        }
+        Ok((place, through_ref))
+    }
+
+    fn cell(&self, address: usize) -> Run<&Object> {
+        let cell = &self.heap[address];
+        match cell.freed_at {
+            Some(line) => self.fail(Problem::UseAfterFree, format!("use after free: #{address} was freed on line {line}")),
+            None => Ok(&cell.object),
+        }
+    }
+
+    fn get(&self, place: &Place) -> Run<Value> {
+        let mut value = match &place.root {
+            Root::Local { frame, slot } => self.slot(*frame, *slot)?.value.clone(),
+            Root::Heap(address) => match self.cell(*address)? {
+                Object::Box(value) => value.clone(),
+                Object::Vec { items, .. } => Value::Array(items.clone()),
+                Object::Str { .. } => Value::Heap(*address),
+            },
+        };
+        for &i in &place.path {
+            value = match value {
+                Value::Tuple(parts) | Value::Array(parts) => parts[i].clone(),
+                other => return self.fail(Problem::Other, format!("can't look inside {}", self.show(&other))),
+            };
+        }
+        Ok(value)
+    }
+
+    fn get_mut(&mut self, place: &Place) -> Run<&mut Value> {
+        //checked up front so the borrows below stay simple
+        match &place.root {
+            Root::Local { frame, slot } => {
+                self.slot(*frame, *slot)?;
+            }
+            Root::Heap(address) => {
+                self.cell(*address)?;
+            }
+        }
+
+        let mut path = place.path.as_slice();
+        let mut value = match &place.root {
+            Root::Local { frame, slot } => {
+                let frame = self.frames.iter_mut().find(|f| f.id == *frame).unwrap();
+                &mut frame.slots[*slot].value
+            }
+            Root::Heap(address) => match &mut self.heap[*address].object {
+                Object::Box(value) => value,
+                Object::Vec { items, .. } => {
+                    let Some((first, rest)) = path.split_first() else {
+                        return Err(RuntimeError { line: self.line, problem: Problem::Other, message: String::from("can't assign to a whole Vec, only to its elements") });
+                    };
+                    path = rest;
+                    &mut items[*first]
+                }
+                Object::Str { .. } => {
+                    return Err(RuntimeError { line: self.line, problem: Problem::Other, message: String::from("can't assign into a String") })
+                }
+            },
+        };
+        for &i in path {
+            value = match value {
+                Value::Tuple(parts) | Value::Array(parts) => &mut parts[i],
+                _ => return Err(RuntimeError { line: self.line, problem: Problem::Other, message: String::from("can't look inside that") }),
+            };
+        }
+        Ok(value)
+    }
+
+    fn slot(&self, frame: usize, slot: usize) -> Run<&Slot> {
+        let Some(f) = self.frames.iter().find(|f| f.id == frame) else {
+            return self.fail(Problem::Dangling, "dangling reference: it points into a function that already returned");
+        };
+        let slot = &f.slots[slot];
+        if slot.dead {
+            return self.fail(Problem::Dangling, format!("dangling reference: {} went out of scope", slot.name));
+        }
+        Ok(slot)
+    }
+
+    //Reading a place by value: Copy values get copied, everything else moves
+    fn read(&mut self, expr: &Expr) -> Run<Value> {
+        let (place, through_ref) = self.place(expr)?;
+        let value = self.get(&place)?;
+        let name = self.name_of(&place);
+
+        if let Some(line) = value.moved_at() {
+            return self.fail(Problem::UseAfterMove, format!("use of moved value: {name} was moved on line {line}"));
+        }
+        if value.is_copy() {
+            return Ok(value);
+        }
+
+        //Rust wouldn't allow these, so nothing marks the original as moved: now there are two owners
+        if through_ref || matches!(place.root, Root::Heap(_)) {
+            self.event(format!("copied {name} out from behind a pointer without moving it, so it has two owners now"));
+            return Ok(value);
+        }
+        *self.get_mut(&place)? = Value::Moved(self.line);
+        self.event(format!("moved {name}"));
+        Ok(value)
+    }
+
+    //Like read(), but never moves (println! and method receivers only look)
+    fn peek(&mut self, expr: &Expr) -> Run<Value> {
+        match expr {
+            Expr::Var(_) | Expr::Field(..) | Expr::Index(..) | Expr::Deref(_) => {
+                let (place, _) = self.place(expr)?;
+                let value = self.get(&place)?;
+                if let Some(line) = value.moved_at() {
+                    let name = self.name_of(&place);
+                    return self.fail(Problem::UseAfterMove, format!("use of moved value: {name} was moved on line {line}"));
+                }
+                Ok(value)
+            }
+            _ => self.eval(expr),
+        }
+    }
+
+    pub fn eval(&mut self, expr: &Expr) -> Run<Value> {
+        match expr {
+            Expr::Int(n) => Ok(Value::Int(*n)),
+            Expr::Bool(b) => Ok(Value::Bool(*b)),
+            Expr::Str(s) => Ok(Value::Text(s.clone())),
+            Expr::Var(_) | Expr::Field(..) | Expr::Index(..) | Expr::Deref(_) => self.read(expr),
+            Expr::Borrow { mutable, place } => {
+                let (to, _) = self.place(place)?;
+                //make sure it's not already moved or freed
+                self.peek(place)?;
+                Ok(Value::Ref { to, mutable: *mutable })
+            }
+            Expr::Binary(left, op, right) => {
+                let left = self.eval(left)?;
+                let right = self.eval(right)?;
+                self.binary(left, *op, right)
+            }
+            Expr::Neg(inner) => match self.eval(inner)? {
+                //like a release build: -i64::MIN wraps around to itself
+                Value::Int(n) => Ok(Value::Int(n.wrapping_neg())),
+                other => self.fail(Problem::Other, format!("can't negate {}", self.show(&other))),
+            },
+            Expr::Tuple(parts) => Ok(Value::Tuple(self.eval_all(parts)?)),
+            Expr::Array(parts) => Ok(Value::Array(self.eval_all(parts)?)),
+            Expr::Vec(parts) => {
+                let items = self.eval_all(parts)?;
+                let cap = items.len();
+                Ok(Value::Heap(self.alloc(Object::Vec { items, cap })))
+            }
+            Expr::Call(name, args) => self.call_expr(name, args),
+            Expr::Method(receiver, method, args) => self.method(receiver, method, args),
+            Expr::Print(format, args) => {
+                let mut shown = Vec::new();
+                for arg in args {
+                    let value = self.peek(arg)?;
+                    shown.push(self.display(&value)?);
+                }
+                let text = self.format(format, shown)?;
+                self.event(format!("prints: {text}"));
+                Ok(Value::Unit)
+            }
+        }
+    }
+
+    fn eval_all(&mut self, exprs: &[Expr]) -> Run<Vec<Value>> {
+        exprs.iter().map(|e| self.eval(e)).collect()
+    }
+
+    fn binary(&self, left: Value, op: BinOp, right: Value) -> Run<Value> {
+        let (Value::Int(a), Value::Int(b)) = (&left, &right) else {
+            return match op {
+                BinOp::Eq => Ok(Value::Bool(left == right)),
+                BinOp::Ne => Ok(Value::Bool(left != right)),
+                _ => self.fail(Problem::Other, format!("can't do math with {} and {}", self.show(&left), self.show(&right))),
+            };
+        };
+        let (a, b) = (*a, *b);
+        Ok(match op {
+            BinOp::Add => Value::Int(a.wrapping_add(b)),
+            BinOp::Sub => Value::Int(a.wrapping_sub(b)),
+            BinOp::Mul => Value::Int(a.wrapping_mul(b)),
+            BinOp::Eq => Value::Bool(a == b),
+            BinOp::Ne => Value::Bool(a != b),
+            BinOp::Lt => Value::Bool(a < b),
+            BinOp::Gt => Value::Bool(a > b),
+            BinOp::Le => Value::Bool(a <= b),
+            BinOp::Ge => Value::Bool(a >= b),
+        })
+    }
+
+    fn call_expr(&mut self, name: &str, args: &[Expr]) -> Run<Value> {
+        let mut values = self.eval_all(args)?;
+        match (name, values.len()) {
+            ("String::from", 1) => match values.remove(0) {
+                Value::Text(text) => {
+                    let cap = text.len();
+                    Ok(Value::Heap(self.alloc(Object::Str { text, cap })))
+                }
+                other => self.fail(Problem::Other, format!("String::from needs a string literal, not {}", self.show(&other))),
+            },
+            ("String::new", 0) => Ok(Value::Heap(self.alloc(Object::Str { text: String::new(), cap: 0 }))),
+            ("Box::new", 1) => {
+                let value = values.remove(0);
+                Ok(Value::Heap(self.alloc(Object::Box(value))))
+            }
+            ("drop", 1) => {
+                let value = values.remove(0);
+                self.drop_value(value, "drop()")?;
+                Ok(Value::Unit)
+            }
+            _ => {
+                self.event(format!("calls {name}"));
+                self.call(name, values)
+            }
+        }
+    }
+
+    fn method(&mut self, receiver: &Expr, method: &str, args: &[Expr]) -> Run<Value> {
+        match method {
+            "clone" => {
+                let value = self.peek(receiver)?;
+                let value = self.follow_refs(value)?;
+                self.deep_clone(&value)
+            }
+            "len" => {
+                let value = self.peek(receiver)?;
+                match self.follow_refs(value)? {
+                    Value::Heap(address) => match self.cell(address)? {
+                        Object::Str { text, .. } => Ok(Value::Int(text.len() as i64)),
+                        Object::Vec { items, .. } => Ok(Value::Int(items.len() as i64)),
+                        Object::Box(Value::Array(items)) => Ok(Value::Int(items.len() as i64)),
+                        Object::Box(_) => self.fail(Problem::Other, "a Box doesn't have a length"),
+                    },
+                    Value::Array(items) => Ok(Value::Int(items.len() as i64)),
+                    Value::Text(text) => Ok(Value::Int(text.len() as i64)),
+                    other => self.fail(Problem::Other, format!("{} doesn't have a length", self.show(&other))),
+                }
+            }
+            "is_positive" | "abs" => {
+                let value = self.peek(receiver)?;
+                //x.abs() works on a Box<i32> too (implicit dereference)
+                let value = match self.follow_refs(value)? {
+                    Value::Heap(address) => match self.cell(address)? {
+                        Object::Box(inner) => inner.clone(),
+                        _ => Value::Heap(address),
+                    },
+                    other => other,
+                };
+                match value {
+                    Value::Int(n) if method == "abs" => Ok(Value::Int(n.wrapping_abs())),
+                    Value::Int(n) => Ok(Value::Bool(n > 0)),
+                    other => self.fail(Problem::Other, format!("{method} needs a number, not {}", self.show(&other))),
+                }
+            }
+            "push" | "push_str" => {
+                let [arg] = args else {
+                    return self.fail(Problem::Other, format!("{method} takes one argument"));
+                };
+                let value = self.eval(arg)?;
+                let (place, _) = self.auto_deref(receiver)?;
+                self.push(&place, method, value)?;
+                Ok(Value::Unit)
+            }
+            _ => self.fail(Problem::Other, format!("the toy language doesn't know the method {method}")),
+        }
+    }
+
+    fn follow_refs(&self, mut value: Value) -> Run<Value> {
+        while let Value::Ref { to, .. } = value {
+            value = self.get(&to)?;
+        }
+        Ok(value)
+    }
+
+    //When there's no room left, pushing moves everything into a bigger allocation and frees the old one.
+    //That's what leaves references into the old one dangling
+    fn push(&mut self, owner: &Place, method: &str, value: Value) -> Run<()> {
+        let Value::Heap(old) = self.get(owner)? else {
+            return self.fail(Problem::Other, format!("can only {method} onto a String or Vec"));
+        };
+        let added = match (&value, method) {
+            (Value::Text(text), "push_str") => text.clone(),
+            (Value::Heap(address), "push_str") => match self.cell(*address)? {
+                Object::Str { text, .. } => text.clone(),
+                _ => return self.fail(Problem::Other, "push_str needs a string"),
+            },
+            (_, "push_str") => return self.fail(Problem::Other, "push_str needs a string"),
+            _ => String::new(),
+        };
+
+        let (full, needed) = match self.cell(old)? {
+            Object::Str { text, cap } if method == "push_str" => (text.len() + added.len() > *cap, text.len() + added.len()),
+            Object::Vec { items, cap } if method == "push" => (items.len() == *cap, items.len() + 1),
+            _ => return self.fail(Problem::Other, format!("can't {method} onto #{old}")),
+        };
+
+        let mut address = old;
+        if full {
+            let bigger = match &self.heap[old].object {
+                Object::Str { text, cap } => Object::Str { text: text.clone(), cap: needed.max(cap * 2) },
+                Object::Vec { items, cap } => Object::Vec { items: items.clone(), cap: needed.max(cap * 2) },
+                Object::Box(_) => unreachable!(),
+            };
+            address = self.alloc(bigger);
+            self.heap[old].freed_at = Some(self.line);
+            self.event(format!("{method} ran out of room: moved everything from #{old} to #{address} and freed #{old}"));
+            *self.get_mut(owner)? = Value::Heap(address);
+        }
+
+        match &mut self.heap[address].object {
+            Object::Str { text, .. } => text.push_str(&added),
+            Object::Vec { items, .. } => items.push(value),
+            Object::Box(_) => unreachable!(),
+        }
+        Ok(())
+    }
+
+    fn deep_clone(&mut self, value: &Value) -> Run<Value> {
+        match value {
+            Value::Heap(address) => {
+                let object = match self.cell(*address)? {
+                    Object::Str { text, .. } => Object::Str { text: text.clone(), cap: text.len() },
+                    Object::Vec { items, .. } => Object::Vec { items: items.clone(), cap: items.len() },
+                    Object::Box(inner) => Object::Box(inner.clone()),
+                };
+                //what's inside gets cloned too, so the clone owns its own copies
+                let object = match object {
+                    Object::Vec { items, cap } => {
+                        let items = items.iter().map(|i| self.deep_clone(i)).collect::<Run<Vec<Value>>>()?;
+                        Object::Vec { items, cap }
+                    }
+                    Object::Box(inner) => Object::Box(self.deep_clone(&inner)?),
+                    object => object,
+                };
+                Ok(Value::Heap(self.alloc(object)))
+            }
+            Value::Tuple(parts) => Ok(Value::Tuple(parts.iter().map(|p| self.deep_clone(p)).collect::<Run<_>>()?)),
+            Value::Array(parts) => Ok(Value::Array(parts.iter().map(|p| self.deep_clone(p)).collect::<Run<_>>()?)),
+            other => Ok(other.clone()),
+        }
+    }
+
+    //println!'s "{}" and "{name}"
+    fn format(&mut self, format: &str, args: Vec<String>) -> Run<String> {
+        let mut out = String::new();
+        let mut args = args.into_iter();
+        let mut rest = format;
+        while let Some(open) = rest.find('{') {
+            out.push_str(&rest[..open]);
+            let Some(close) = rest[open..].find('}') else { break };
+            let inside = &rest[open + 1..open + close];
+            let name = inside.split(':').next().unwrap_or("");
+            if name.is_empty() {
+                out.push_str(&args.next().unwrap_or_default());
+            } else {
+                let value = self.peek(&Expr::Var(name.to_string()))?;
+                out.push_str(&self.display(&value)?);
+            }
+            rest = &rest[open + close + 1..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+
+    //What println! would print. Goes through references, so a dangling one is caught here
+    fn display(&self, value: &Value) -> Run<String> {
+        Ok(match value {
+            Value::Unit => String::from("()"),
+            Value::Int(n) => n.to_string(),
+            Value::Bool(b) => b.to_string(),
+            Value::Text(text) => text.clone(),
+            Value::Ref { to, .. } => self.display(&self.get(to)?)?,
+            Value::Heap(address) => match self.cell(*address)? {
+                Object::Str { text, .. } => text.clone(),
+                Object::Box(inner) => self.display(inner)?,
+                Object::Vec { items, .. } => self.display(&Value::Array(items.clone()))?,
+            },
+            Value::Tuple(parts) | Value::Array(parts) => {
+                let parts = parts.iter().map(|p| self.display(p)).collect::<Run<Vec<_>>>()?;
+                let (open, close) = if matches!(value, Value::Tuple(_)) { ("(", ")") } else { ("[", "]") };
+                format!("{open}{}{close}", parts.join(", "))
+            }
+            Value::Moved(line) => return self.fail(Problem::UseAfterMove, format!("use of a value moved on line {line}")),
+        })
+    }
+
+    fn record(&mut self, note: Option<String>) {
+        let frame = self.frames.last().expect("there's always a frame while running");
+        let locals = frame
+            .slots
+            .iter()
+            .filter(|s| !s.dead)
+            .map(|s| Local {
+                name: s.name.clone(),
+                mutable: s.mutable,
+                moved: s.value.moved_at().is_some(),
+                reference: match s.value {
+                    Value::Ref { mutable, .. } => Some(mutable),
+                    _ => None,
+                },
+                chain: self.chain(&s.value),
+            })
+            .collect();
+        let step = Step { line: self.line, note, memory: self.snapshot(), locals, events: self.events.len() };
+        self.steps.push(step);
+    }
+
+    //Follows pointers as far as they go
+    fn chain(&self, value: &Value) -> String {
+        let mut parts = Vec::new();
+        let mut value = value.clone();
+        loop {
+            match value {
+                Value::Ref { to, .. } => {
+                    parts.push(self.name_of(&to));
+                    match self.get(&to) {
+                        Ok(next) => value = next,
+                        Err(e) => {
+                            parts.push(format!("({})", e.message));
+                            break;
+                        }
+                    }
+                }
+                Value::Heap(address) => {
+                    let cell = &self.heap[address];
+                    let freed = if cell.freed_at.is_some() { " (freed)" } else { "" };
+                    parts.push(format!("#{address} {}{freed}", self.show_cell(address)));
+                    match &cell.object {
+                        Object::Box(inner) if cell.freed_at.is_none() => value = inner.clone(),
+                        _ => break,
+                    }
+                }
+                Value::Moved(line) => {
+                    parts.push(format!("(moved out on line {line})"));
+                    break;
+                }
+                other => {
+                    parts.push(self.show(&other));
+                    break;
+                }
+            }
+        }
+        parts.join(" --> ")
+    }
+
+    //For diagrams and messages: never fails, even on freed memory
+    pub fn show(&self, value: &Value) -> String {
+        match value {
+            Value::Text(text) => format!("{text:?}"),
+            Value::Heap(address) => format!("#{address}"),
+            Value::Ref { to, mutable } => format!("{}{}", if *mutable { "&mut " } else { "&" }, self.name_of(to)),
+            Value::Tuple(parts) => format!("({})", parts.iter().map(|p| self.show(p)).collect::<Vec<_>>().join(", ")),
+            Value::Array(parts) => format!("[{}]", parts.iter().map(|p| self.show(p)).collect::<Vec<_>>().join(", ")),
+            Value::Moved(_) => String::from("(moved)"),
+            Value::Unit => String::from("()"),
+            Value::Int(n) => n.to_string(),
+            Value::Bool(b) => b.to_string(),
+        }
+    }
+
+    pub fn show_cell(&self, address: usize) -> String {
+        match &self.heap[address].object {
+            Object::Str { text, .. } => format!("{text:?}"),
+            Object::Vec { items, .. } => self.show(&Value::Array(items.clone())),
+            Object::Box(inner) => format!("Box({})", self.show(inner)),
+        }
+    }
+
+    //"no_im_not", "name.1", "#2[0]"
+    pub fn name_of(&self, place: &Place) -> String {
+        let mut name = match &place.root {
+            Root::Local { frame, slot } => match self.frames.iter().find(|f| f.id == *frame) {
+                Some(f) => f.slots[*slot].name.clone(),
+                None => String::from("(gone)"),
+            },
+            Root::Heap(address) => format!("#{address}"),
+        };
+        let is_tuple_step = |value: &Value| matches!(value, Value::Tuple(_));
+        let mut value = match &place.root {
+            Root::Local { frame, slot } => self.frames.iter().find(|f| f.id == *frame).map(|f| f.slots[*slot].value.clone()),
+            Root::Heap(_) => None,
+        };
+        for &i in &place.path {
+            if value.as_ref().is_some_and(is_tuple_step) {
+                name.push_str(&format!(".{i}"));
+            } else {
+                name.push_str(&format!("[{i}]"));
+            }
+            value = match value {
+                Some(Value::Tuple(parts)) | Some(Value::Array(parts)) => parts.get(i).cloned(),
+                _ => None,
+            };
+        }
+        name
+    }
+
+    //The machine's state as a memory diagram
+    pub fn snapshot(&self) -> Memory {
+        let mut memory = Memory::new();
+        for frame in &self.frames {
+            memory.push_frame(&frame.function);
+            for slot in frame.slots.iter().filter(|s| !s.dead) {
+                self.flatten(&mut memory, &slot.name, &slot.value);
+            }
+        }
+        for (address, cell) in self.heap.iter().enumerate() {
+            let owns = match &cell.object {
+                Object::Vec { items, .. } => items.iter().flat_map(owned).collect(),
+                Object::Box(inner) => owned(inner),
+                Object::Str { .. } => Vec::new(),
+            };
+            memory.heap.push(memory::Allocation { contents: self.show_cell(address), freed: cell.freed_at.is_some(), owns });
+        }
+        memory
+    }
+
+    //Tuples and arrays become one row per element: name.0, name.1...
+    fn flatten(&self, memory: &mut Memory, name: &str, value: &Value) {
+        match value {
+            Value::Tuple(parts) => {
+                for (i, part) in parts.iter().enumerate() {
+                    self.flatten(memory, &format!("{name}.{i}"), part);
+                }
+            }
+            Value::Array(parts) if parts.iter().any(|p| !matches!(p, Value::Int(_) | Value::Bool(_))) => {
+                for (i, part) in parts.iter().enumerate() {
+                    self.flatten(memory, &format!("{name}[{i}]"), part);
+                }
+            }
+            Value::Moved(_) => {
+                memory.bind(name, memory::Value::Plain(String::new()));
+                memory.take(name);
+            }
+            Value::Heap(address) => memory.bind(name, memory::Value::Owns(*address)),
+            Value::Ref { to, mutable } => memory.bind(name, memory::Value::Ref { to: self.target(to), mutable: *mutable }),
+            other => memory.bind(name, memory::Value::Plain(self.show(other))),
+        }
+    }
+
+    fn target(&self, place: &Place) -> memory::Target {
+        match &place.root {
+            Root::Local { frame, .. } => memory::Target::Slot {
+                frame: self.frames.iter().position(|f| f.id == *frame),
+                name: self.name_of(place),
+            },
+            Root::Heap(address) => memory::Target::Heap { address: *address, index: place.path.first().copied() },
+        }
+    }
+}
+
+//Heap cells directly owned by a value
+fn owned(value: &Value) -> Vec<usize> {
+    match value {
+        Value::Heap(address) => vec![*address],
+        Value::Tuple(parts) | Value::Array(parts) => parts.iter().flat_map(owned).collect(),
+        _ => Vec::new(),
+    }
+}
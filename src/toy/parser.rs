@@ -0,0 +1,452 @@
+use super::ast::*;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Int(i64),
+    Str(String),
+    //punctuation and operators, like "(", "&", "+=", "->", "::"
+    Sym(&'static str),
+}
+
+//The most elements [x; n] can repeat
+const MAX_REPEAT: i64 = 1000;
+
+//Longest first, so "==" isn't read as "=" "="
+const SYMBOLS: [&str; 29] = [
+    "::", "->", "==", "!=", "<=", ">=", "+=", "-=", "(", ")", "{", "}", "[", "]", ",", ";", ":", ".", "&", "*", "+",
+    "-", "=", "<", ">", "!", "'", "|", "/",
+];
+
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+fn lex(src: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = src.chars().collect();
+    let mut i = 0;
+    let mut line = 1;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            line += 1;
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '_') {
+                i += 1;
+            }
+            let digits: String = chars[start..i].iter().filter(|&&c| c != '_').collect();
+            let n = digits.parse().map_err(|_| ParseError { line, message: format!("{digits} is too big") })?;
+            tokens.push((Token::Int(n), line));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Ident(chars[start..i].iter().collect()), line));
+        } else if c == '"' {
+            let start_line = line;
+            let mut text = String::new();
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                if chars[i] == '\n' {
+                    line += 1;
+                }
+                text.push(chars[i]);
+                i += 1;
+            }
+            if i == chars.len() {
+                return Err(ParseError { line: start_line, message: String::from("this string never ends") });
+            }
+            i += 1;
+            tokens.push((Token::Str(text), start_line));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let sym = SYMBOLS
+                .iter()
+                .find(|s| rest.starts_with(**s))
+                .ok_or_else(|| ParseError { line, message: format!("didn't expect `{c}`") })?;
+            i += sym.chars().count();
+            tokens.push((Token::Sym(sym), line));
+        }
+    }
+    Ok(tokens)
+}
+
+pub fn parse(src: &str) -> Result<Program, ParseError> {
+    let mut parser = Parser { tokens: lex(src)?, pos: 0 };
+    let mut functions = Vec::new();
+    while !parser.done() {
+        functions.push(parser.function()?);
+    }
+    Ok(Program { functions })
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+type Parsed<T> = Result<T, ParseError>;
+
+impl Parser {
+    fn done(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens.get(self.pos).or(self.tokens.last()).map(|(_, l)| *l).unwrap_or(1)
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Parsed<T> {
+        Err(ParseError { line: self.line(), message: message.into() })
+    }
+
+    fn next(&mut self) -> Parsed<Token> {
+        match self.tokens.get(self.pos) {
+            Some((t, _)) => {
+                self.pos += 1;
+                Ok(t.clone())
+            }
+            None => self.error("the program ended too early"),
+        }
+    }
+
+    fn is(&self, sym: &str) -> bool {
+        matches!(self.peek(), Some(Token::Sym(s)) if *s == sym)
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(w)) if w == word)
+    }
+
+    //Skips the symbol if it's next
+    fn eat(&mut self, sym: &str) -> bool {
+        let found = self.is(sym);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        let found = self.is_word(word);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, sym: &str) -> Parsed<()> {
+        if self.eat(sym) {
+            Ok(())
+        } else {
+            self.error(format!("expected `{sym}` here"))
+        }
+    }
+
+    fn ident(&mut self) -> Parsed<String> {
+        match self.peek() {
+            Some(Token::Ident(name)) => {
+                let name = name.clone();
+                self.pos += 1;
+                Ok(name)
+            }
+            _ => self.error("expected a name here"),
+        }
+    }
+
+    fn function(&mut self) -> Parsed<Function> {
+        let line = self.line();
+        if !self.eat_word("fn") {
+            return self.error("expected `fn`");
+        }
+        let name = self.ident()?;
+        self.expect("(")?;
+
+        let mut params = Vec::new();
+        while !self.eat(")") {
+            let mutable = self.eat_word("mut");
+            let name = self.ident()?;
+            self.expect(":")?;
+            params.push(Param { name, mutable, ty: self.ty()? });
+            if !self.is(")") {
+                self.expect(",")?;
+            }
+        }
+
+        let returns = if self.eat("->") { Some(self.ty()?) } else { None };
+        let body = self.block()?;
+        Ok(Function { name, params, returns, body, line })
+    }
+
+    fn ty(&mut self) -> Parsed<Type> {
+        if self.eat("&") {
+            //lifetimes like &'static str are allowed, and ignored
+            if self.eat("'") {
+                self.ident()?;
+            }
+            let mutable = self.eat_word("mut");
+            return Ok(Type::Ref { mutable, inner: Box::new(self.ty()?) });
+        }
+        if self.eat("(") {
+            let mut parts = Vec::new();
+            while !self.eat(")") {
+                parts.push(self.ty()?);
+                if !self.is(")") {
+                    self.expect(",")?;
+                }
+            }
+            return Ok(Type::Tuple(parts));
+        }
+        if self.eat("[") {
+            let inner = self.ty()?;
+            //[i32; 3] and [i32] are the same thing here
+            if self.eat(";") {
+                self.next()?;
+            }
+            self.expect("]")?;
+            return Ok(Type::Array(Box::new(inner)));
+        }
+
+        let name = self.ident()?;
+        if self.eat("<") {
+            let inner = self.ty()?;
+            self.expect(">")?;
+            return Ok(Type::Generic(name, Box::new(inner)));
+        }
+        Ok(Type::Named(name))
+    }
+
+    fn block(&mut self) -> Parsed<Block> {
+        self.expect("{")?;
+        let mut stmts = Vec::new();
+        while !self.eat("}") {
+            stmts.push(self.stmt()?);
+        }
+        Ok(stmts)
+    }
+
+    fn stmt(&mut self) -> Parsed<Stmt> {
+        let line = self.line();
+
+        let kind = if self.eat_word("let") {
+            let mutable = self.eat_word("mut");
+            let name = self.ident()?;
+            let ty = if self.eat(":") { Some(self.ty()?) } else { None };
+            self.expect("=")?;
+            let value = self.expr()?;
+            self.expect(";")?;
+            StmtKind::Let { name, mutable, ty, value }
+        } else if self.eat_word("if") {
+            let cond = self.expr()?;
+            let then = self.block()?;
+            let otherwise = if self.eat_word("else") { self.block()? } else { Vec::new() };
+            StmtKind::If { cond, then, otherwise }
+        } else if self.eat_word("return") {
+            let value = if self.is(";") { None } else { Some(self.expr()?) };
+            self.expect(";")?;
+            StmtKind::Return(value)
+        } else {
+            let expr = self.expr()?;
+            if self.eat("=") {
+                let value = self.expr()?;
+                self.expect(";")?;
+                StmtKind::Assign { place: expr, value }
+            } else if self.is("+=") || self.is("-=") {
+                let op = if self.eat("+=") { BinOp::Add } else { self.next()?; BinOp::Sub };
+                let rhs = self.expr()?;
+                self.expect(";")?;
+                let value = Expr::Binary(Box::new(expr.clone()), op, Box::new(rhs));
+                StmtKind::Assign { place: expr, value }
+            } else if self.is("}") {
+                //a block ending in an expression with no semicolon returns it, like Rust
+                StmtKind::Return(Some(expr))
+            } else {
+                self.expect(";")?;
+                StmtKind::Expr(expr)
+            }
+        };
+        Ok(Stmt { kind, line })
+    }
+
+    fn expr(&mut self) -> Parsed<Expr> {
+        let left = self.additive()?;
+        let op = match self.peek() {
+            Some(Token::Sym("==")) => BinOp::Eq,
+            Some(Token::Sym("!=")) => BinOp::Ne,
+            Some(Token::Sym("<")) => BinOp::Lt,
+            Some(Token::Sym(">")) => BinOp::Gt,
+            Some(Token::Sym("<=")) => BinOp::Le,
+            Some(Token::Sym(">=")) => BinOp::Ge,
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+        Ok(Expr::Binary(Box::new(left), op, Box::new(self.additive()?)))
+    }
+
+    fn additive(&mut self) -> Parsed<Expr> {
+        let mut left = self.multiplicative()?;
+        loop {
+            let op = if self.eat("+") {
+                BinOp::Add
+            } else if self.eat("-") {
+                BinOp::Sub
+            } else {
+                return Ok(left);
+            };
+            left = Expr::Binary(Box::new(left), op, Box::new(self.multiplicative()?));
+        }
+    }
+
+    fn multiplicative(&mut self) -> Parsed<Expr> {
+        let mut left = self.unary()?;
+        while self.eat("*") {
+            left = Expr::Binary(Box::new(left), BinOp::Mul, Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Parsed<Expr> {
+        if self.eat("&") {
+            let mutable = self.eat_word("mut");
+            return Ok(Expr::Borrow { mutable, place: Box::new(self.unary()?) });
+        }
+        if self.eat("*") {
+            return Ok(Expr::Deref(Box::new(self.unary()?)));
+        }
+        if self.eat("-") {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.postfix()
+    }
+
+    fn postfix(&mut self) -> Parsed<Expr> {
+        let mut expr = self.primary()?;
+        loop {
+            if self.eat("[") {
+                let index = self.expr()?;
+                self.expect("]")?;
+                expr = Expr::Index(Box::new(expr), Box::new(index));
+            } else if self.eat(".") {
+                match self.next()? {
+                    Token::Int(n) => expr = Expr::Field(Box::new(expr), n as usize),
+                    Token::Ident(method) => {
+                        let args = self.args("(", ")")?;
+                        expr = Expr::Method(Box::new(expr), method, args);
+                    }
+                    _ => return self.error("expected a field number or a method after `.`"),
+                }
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    //Comma separated expressions between open and close, like (a, b) or [1, 2, 3]
+    fn args(&mut self, open: &str, close: &str) -> Parsed<Vec<Expr>> {
+        self.expect(open)?;
+        let mut args = Vec::new();
+        while !self.eat(close) {
+            args.push(self.expr()?);
+            if !self.is(close) {
+                self.expect(",")?;
+            }
+        }
+        Ok(args)
+    }
+
+    fn primary(&mut self) -> Parsed<Expr> {
+        match self.next()? {
+            Token::Int(n) => Ok(Expr::Int(n)),
+            Token::Str(s) => Ok(Expr::Str(s)),
+            Token::Ident(word) if word == "true" => Ok(Expr::Bool(true)),
+            Token::Ident(word) if word == "false" => Ok(Expr::Bool(false)),
+            Token::Ident(word) if (word == "vec" || word == "println") && self.is("!") => {
+                self.pos += 1;
+                if word == "vec" {
+                    return Ok(Expr::Vec(self.args("[", "]")?));
+                }
+                let mut args = self.args("(", ")")?;
+                match args.first() {
+                    Some(Expr::Str(_)) => {}
+                    _ => return self.error("println! needs a string first"),
+                }
+                let Expr::Str(format) = args.remove(0) else { unreachable!() };
+                Ok(Expr::Print(format, args))
+            }
+            Token::Ident(mut name) => {
+                //String::from, Box::new...
+                while self.is("::") {
+                    self.pos += 1;
+                    name = format!("{name}::{}", self.ident()?);
+                }
+                if self.is("(") {
+                    Ok(Expr::Call(name, self.args("(", ")")?))
+                } else {
+                    Ok(Expr::Var(name))
+                }
+            }
+            Token::Sym("(") => {
+                let first = self.expr()?;
+                if self.eat(")") {
+                    return Ok(first);
+                }
+                let mut parts = vec![first];
+                while self.eat(",") {
+                    if self.is(")") {
+                        break;
+                    }
+                    parts.push(self.expr()?);
+                }
+                self.expect(")")?;
+                Ok(Expr::Tuple(parts))
+            }
+            Token::Sym("[") => {
+                if self.eat("]") {
+                    return Ok(Expr::Array(Vec::new()));
+                }
+                let first = self.expr()?;
+                //[1; 3] is [1, 1, 1]
+                if self.eat(";") {
+                    let Token::Int(n) = self.next()? else {
+                        return self.error("expected how many after `;`");
+                    };
+                    //every element gets drawn, so a huge one is a mistake, not something to allocate
+                    if n > MAX_REPEAT {
+                        return self.error(format!("[_; {n}] is too long, the toy language only goes up to {MAX_REPEAT} elements"));
+                    }
+                    self.expect("]")?;
+                    return Ok(Expr::Array(vec![first; n as usize]));
+                }
+                let mut items = vec![first];
+                while self.eat(",") {
+                    if self.is("]") {
+                        break;
+                    }
+                    items.push(self.expr()?);
+                }
+                self.expect("]")?;
+                Ok(Expr::Array(items))
+            }
+            _ => {
+                self.pos -= 1;
+                self.error("expected a value here")
+            }
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//Toy versions of the lessons' examples, so they can be run (and broken) without rustc.
+//Each one is (name, what it shows, source)
+
+pub const EXAMPLES: [(&str, &str, &str); 11] = [
+    ("add_ten", "explain_rust_memory: using no_im_not after it moved into add_ten", ADD_TEN),
+    ("make_it_safe", "make_it_safe: clone first, so only the clone moves", MAKE_IT_SAFE),
+    ("safe_copy", "case4: copying an i32 out through a reference is fine", SAFE_COPY),
+    ("unsafe_copy", "case4: copying a String out through a reference frees it twice", UNSAFE_COPY),
+    ("dereferencing", "explain_dereferencing: following a Box (and references to it)", DEREFERENCING),
+    ("aliasing", "explain_aliasing: pushing while num still points into the Vec", ALIASING),
+    ("aliasing_fixed", "explain_aliasing: pushing after num's last use", ALIASING_FIXED),
+    ("lifetimes", "explain_lifetimes: ref_1 hands off to ref_2, then a borrow inside an if", LIFETIMES),
+    ("tuple_fields", "case5: borrowing name.0 while changing name.1 is fine", TUPLE_FIELDS),
+    ("get_first", "case5: the same thing through get_first, which rustc can't see inside", GET_FIRST),
+    ("array_read_write", "case6: a[0] and a[1] at once is safe, but rustc only sees a[_]", ARRAY_READ_WRITE),
+];
+
+pub fn example(name: &str) -> Option<&'static str> {
+    EXAMPLES.iter().find(|(n, _, _)| *n == name).map(|(_, _, src)| *src)
+}
+
+const ADD_TEN: &str = r#"fn main() {
+    let no_im_not = String::from("nine");
+    let stupid = add_ten(no_im_not);
+    println!("What's {stupid}");
+    println!("{no_im_not}");
+}
+
+fn add_ten(mut number: String) -> String {
+    number.push_str(" plus ten");
+    number
+}
+"#;
+
+const MAKE_IT_SAFE: &str = r#"fn main() {
+    let quick_maths = String::from("nine");
+    let quick_maths_clone = quick_maths.clone();
+    let im_smart = add_ten(quick_maths_clone);
+    println!("What's {im_smart}?");
+    println!("It's {quick_maths}teen.");
+}
+
+fn add_ten(mut number: String) -> String {
+    number.push_str(" plus ten");
+    number
+}
+"#;
+
+const SAFE_COPY: &str = r#"fn main() {
+    let v: Vec<i32> = vec![0, 1, 2];
+    let n_ref: &i32 = &v[0];
+    let n = *n_ref;
+    println!("{n}");
+}
+"#;
+
+const UNSAFE_COPY: &str = r#"fn main() {
+    let v: Vec<String> = vec![String::from("Hello"), String::from("World")];
+    let s_ref: &String = &v[0];
+    let s = *s_ref;
+    println!("{s}");
+}
+"#;
+
+const DEREFERENCING: &str = r#"fn main() {
+    let mut a: Box<i32> = Box::new(1);
+    let b: i32 = *a;
+    *a += 1;
+    let c: &Box<i32> = &a;
+    let d: i32 = **c;
+    let e: &i32 = &*a;
+    let f: i32 = *e;
+    println!("a: {a}, b: {b}, c: {c}, d: {d}, e: {e}, f: {f}");
+    let x = Box::new(-42);
+    println!("{}", x.abs());
+}
+"#;
+
+const ALIASING: &str = r#"fn main() {
+    let mut vec: Vec<i32> = vec![1, 2, 3];
+    let num: &i32 = &vec[2];
+    vec.push(4);
+    println!("Third element is {}", *num);
+}
+"#;
+
+const ALIASING_FIXED: &str = r#"fn main() {
+    let mut vec: Vec<i32> = vec![1, 2, 3];
+    let num: &i32 = &vec[2];
+    println!("Third element is {}", *num);
+    vec.push(4);
+}
+"#;
+
+const LIFETIMES: &str = r#"fn main() {
+    let mut original: Box<[i32; 3]> = Box::new([1; 3]);
+    let ref_1 = &mut original[1];
+    *ref_1 = 5;
+    let ref_2 = &*ref_1;
+    println!("{}", *ref_2);
+    (*original)[1] = 1;
+
+    let mut pos_or_neg: Box<[i32; 10]> = Box::new([1, -2, 3, 4, -5, -6, 7, 8, 9, -10]);
+    let rng_i = 3;
+    let rng_value = pos_or_neg[rng_i];
+    if rng_value.is_positive() {
+        let conditional_ref = &mut pos_or_neg[rng_i];
+        *conditional_ref = rng_value * -1;
+        println!("Changed position {rng_i} to be negative (now {conditional_ref})");
+    } else {
+        println!("Position {rng_i} ({rng_value}) is already negative");
+    }
+    println!("Now it's {}", pos_or_neg[rng_i]);
+}
+"#;
+
+const TUPLE_FIELDS: &str = r#"fn main() {
+    let mut name = (String::from("Ferris"), String::from("Rustacean"));
+    let first = &name.0;
+    name.1.push_str(", Esq");
+    println!("{first} {}", name.1);
+}
+"#;
+
+const GET_FIRST: &str = r#"fn main() {
+    let mut name = (String::from("Ferris"), String::from("Rustacean"));
+    let first = get_first(&name);
+    name.1.push_str(", Esq");
+    println!("{first} {}", name.1);
+}
+
+fn get_first(name: &(String, String)) -> &String {
+    &name.0
+}
+"#;
+
+const ARRAY_READ_WRITE: &str = r#"fn main() {
+    let mut a = [0, 1, 2, 3];
+    let x = &mut a[0];
+    let y = &a[1];
+    *x += *y;
+    println!("{a:?}");
+}
+"#;
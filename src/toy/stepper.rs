@@ -0,0 +1,176 @@
+use super::borrowck::{self, Calls, Checked, Precision};
+use super::interp::{self, Local, Step};
+use super::parser;
+use crate::lesson::Context;
+use crate::memory;
+use crate::output::say;
+use crate::permissions::Perms;
+
+//Runs a program one statement at a time. After each one it draws the stack and heap,
+//the borrows that are still alive and what permissions every variable has left.
+//Going back just shows an earlier step again: every step is recorded up front
+pub fn step(ctx: &mut Context, src: &str) {
+    let program = match parser::parse(src) {
+        Ok(program) => program,
+        Err(e) => {
+            say!(ctx, "line {}: {}", e.line, e.message);
+            return;
+        }
+    };
+    let (machine, error) = interp::run(&program);
+    let checked = borrowck::check(&program, Precision::Rustc, Calls::Signature);
+    let lines: Vec<&str> = src.lines().collect();
+    let steps = &machine.steps;
+    if steps.is_empty() {
+        match &error {
+            Some(e) => say!(ctx, "Line {} can't run: {}", e.line, e.message),
+            None => say!(ctx, "There's nothing to step through."),
+        }
+        return;
+    }
+
+    let mut at = 0;
+    show(ctx, &machine, &checked, &lines, at);
+    loop {
+        say!(ctx, "\n(next, back, inspect <variable>, or quit)");
+        let Some(command) = ctx.input.read_line() else { break };
+        let words: Vec<&str> = command.split_whitespace().collect();
+        match words.as_slice() {
+            [] | ["next"] | ["n"] => {
+                if at + 1 < steps.len() {
+                    at += 1;
+                    show(ctx, &machine, &checked, &lines, at);
+                } else {
+                    match &error {
+                        Some(e) => say!(ctx, "That's as far as it goes. Line {} can't run: {}", e.line, e.message),
+                        None => say!(ctx, "That was the last step."),
+                    }
+                }
+            }
+            ["back"] | ["b"] => {
+                if at > 0 {
+                    at -= 1;
+                    show(ctx, &machine, &checked, &lines, at);
+                } else {
+                    say!(ctx, "This is the first step.");
+                }
+            }
+            ["inspect", name] | ["i", name] => inspect(ctx, &steps[at], &checked, name),
+            ["quit"] | ["q"] => break,
+            _ => say!(ctx, "Don't know '{}'.", command.trim()),
+        }
+    }
+}
+
+fn show(ctx: &mut Context, machine: &interp::Machine, checked: &Checked, lines: &[&str], at: usize) {
+    let step = &machine.steps[at];
+    let caption = caption(step, lines);
+
+    //what happened since the last step
+    let since = if at == 0 { 0 } else { machine.steps[at - 1].events };
+    say!(ctx, "\n--- step {} of {} ---", at + 1, machine.steps.len());
+    for event in &machine.events[since..step.events] {
+        say!(ctx, "  {}", event.text);
+    }
+    memory::draw(ctx, at as u32 + 1, &caption, &step.memory);
+
+    let live = live_borrows(checked, step);
+    say!(ctx, "  live borrows");
+    if live.is_empty() {
+        say!(ctx, "    (none)");
+    }
+    for lifetime in &live {
+        say!(ctx, "    {:<20} held by {}", lifetime.borrow, lifetime.holders.join(", "));
+    }
+
+    say!(ctx, "  permissions");
+    for (path, perms, why) in permissions(checked, step) {
+        say!(ctx, "    {path:<16} {:<4} {why}", perms.letters());
+    }
+}
+
+//"line 3: let b = &a;", or what happened instead of a line running, like "line 9 (end of main)"
+pub fn caption(step: &Step, lines: &[&str]) -> String {
+    let code = lines.get(step.line.wrapping_sub(1)).map_or("", |l| l.trim());
+    match &step.note {
+        Some(note) => format!("line {} ({note})", step.line),
+        None => format!("line {}: {code}", step.line),
+    }
+}
+
+//Borrows that something still alive after this line is holding on to
+fn live_borrows<'c>(checked: &'c Checked, step: &Step) -> Vec<&'c borrowck::Lifetime> {
+    let Some(live) = checked.live_after.get(&step.line) else { return Vec::new() };
+    let in_scope = |name: &String| step.locals.iter().any(|l| l.name == *name);
+    checked
+        .lifetimes
+        .iter()
+        .filter(|l| in_scope(&l.root) && l.holders.iter().any(|h| live.contains(h) && in_scope(h)))
+        .collect()
+}
+
+//Every variable's permissions (and *x for references), minus what live borrows took away
+fn permissions(checked: &Checked, step: &Step) -> Vec<(String, Perms, String)> {
+    let live = checked.live_after.get(&step.line);
+    let mut rows = Vec::new();
+    for local in &step.locals {
+        if local.moved {
+            rows.push((local.name.clone(), Perms::default(), String::from("moved out")));
+            continue;
+        }
+        //a reference nothing uses anymore is dead, and gives up everything
+        if local.reference.is_some() && !live.is_some_and(|l| l.contains(&local.name)) {
+            rows.push((local.name.clone(), Perms::default(), String::from("dead: not used again")));
+            continue;
+        }
+        rows.push((local.name.clone(), Perms { r: true, w: local.mutable, o: true, f: false }, String::new()));
+        if let Some(mutable) = local.reference {
+            rows.push((format!("*{}", local.name), Perms { r: true, w: mutable, o: false, f: false }, String::new()));
+        }
+    }
+
+    for lifetime in live_borrows(checked, step) {
+        //shared borrows take W and O away, mutable ones take everything
+        let taken = Perms { r: lifetime.mutable, w: true, o: true, f: false };
+        let base = rows.iter().find(|(path, _, _)| *path == lifetime.root).map(|(_, p, _)| *p).unwrap_or_default();
+        //borrowing through a dead reference: there's nothing left to take
+        if base == Perms::default() {
+            continue;
+        }
+        if !rows.iter().any(|(path, _, _)| *path == lifetime.place) {
+            rows.push((lifetime.place.clone(), base, String::new()));
+        }
+        let by = lifetime.holders.join(", ");
+        for (_, perms, why) in rows.iter_mut().filter(|(path, _, _)| *path == lifetime.root || *path == lifetime.place) {
+            let before = *perms;
+            *perms = perms.minus(&taken);
+            if *perms != before && why.is_empty() {
+                *why = format!("borrowed by {by}");
+            }
+        }
+    }
+    rows
+}
+
+fn inspect(ctx: &mut Context, step: &Step, checked: &Checked, name: &str) {
+    let Some(local) = step.locals.iter().rev().find(|l: &&Local| l.name == name) else {
+        let names: Vec<&str> = step.locals.iter().map(|l| l.name.as_str()).collect();
+        say!(ctx, "There's no {name} here. In scope: {}", names.join(", "));
+        return;
+    };
+    say!(ctx, "{name} --> {}", local.chain);
+
+    for (path, perms, why) in permissions(checked, step) {
+        if path == name || path.trim_start_matches('*') == name {
+            say!(ctx, "  {path:<16} {:<4} {why}", perms.letters());
+        }
+    }
+    for lifetime in live_borrows(checked, step) {
+        if lifetime.holders.iter().any(|h| h == name) {
+            say!(ctx, "  holds {} (alive until its last use)", lifetime.borrow);
+        }
+        if lifetime.root == name {
+            say!(ctx, "  lent out as {} to {}", lifetime.borrow, lifetime.holders.join(", "));
+        }
+    }
+}
@@ -1,5 +1,8 @@
-pub fn explain() {
-    println!("Vectors!");
+use crate::lesson::Context;
+use crate::output::say;
+
+pub fn explain(ctx: &mut Context) {
+    say!(ctx, "Vectors!");
 
     //Vectors have variable length, because their data is stored in the heap
     let mut weekdays: Vec<char> = vec!['日', '月', '火', '水', '木'];
@@ -13,8 +16,8 @@ pub fn explain() {
 
     //So you make arrays with [] and vectors with vec![]
     let arr: [char; 5] = ['月', '火', '水', '木', '金'];
-    println!("The array has length {}", arr.len());
+    say!(ctx, "The array has length {}", arr.len());
 
     let vec: Vec<char> = vec!['M', 'T', 'W', 'H', 'F'];
-    println!("The vector has length {}", vec.len());
+    say!(ctx, "The vector has length {}", vec.len());
 }
\ No newline at end of file
@@ -0,0 +1,76 @@
+//Starting the binary for the tests that drive it. Each Sandbox has data and cache dirs of its own,
+//so tests running at the same time never share a progress file, and nothing goes in the real ones
+
+//every test file uses a different part of this
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static SANDBOXES: AtomicUsize = AtomicUsize::new(0);
+
+pub struct Sandbox {
+    dir: PathBuf,
+}
+
+impl Sandbox {
+    pub fn new() -> Sandbox {
+        //the process id tells apart test binaries running at the same time, the count the tests inside one
+        let n = SANDBOXES.fetch_add(1, Ordering::Relaxed);
+        let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("sandboxes").join(format!("{}-{n}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("couldn't make a sandbox dir");
+        Sandbox { dir }
+    }
+
+    //XDG_DATA_HOME, where progress goes
+    pub fn data(&self) -> PathBuf {
+        self.dir.join("data")
+    }
+
+    //XDG_CACHE_HOME, where rustc's diagnostics are cached
+    pub fn cache(&self) -> PathBuf {
+        self.dir.join("cache")
+    }
+
+    //Somewhere in the sandbox for a file or dir the test makes, as an argument it can pass on
+    pub fn path(&self, name: &str) -> String {
+        self.dir.join(name).to_string_lossy().into_owned()
+    }
+
+    pub fn command(&self) -> Command {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_ownership"));
+        command.env("XDG_DATA_HOME", self.data()).env("XDG_CACHE_HOME", self.cache());
+        command
+    }
+
+    pub fn output(&self, args: &[&str]) -> Output {
+        self.command().args(args).output().expect("couldn't start the ownership binary")
+    }
+
+    //What it printed, failing the test if it didn't succeed
+    pub fn run(&self, args: &[&str]) -> String {
+        stdout(self.output(args), args)
+    }
+
+    //The same, with `answers` typed in at its prompts (one per line)
+    pub fn answer(&self, args: &[&str], answers: &[&str]) -> String {
+        let file = self.path("answers.txt");
+        fs::write(&file, answers.join("\n") + "\n").expect("couldn't write the answers file");
+        let out = self.command().arg("--input").arg(&file).args(args).output().expect("couldn't start the ownership binary");
+        stdout(out, args)
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+pub fn stdout(out: Output, args: &[&str]) -> String {
+    assert!(out.status.success(), "`ownership {}` failed:\n{}", args.join(" "), String::from_utf8_lossy(&out.stderr));
+    String::from_utf8(out.stdout).expect("ownership should only print utf-8")
+}
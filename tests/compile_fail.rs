@@ -0,0 +1,89 @@
+//Every line the lessons comment out because "it doesn't compile" has a fixture in tests/compile_fail.
+//Each fixture says which lesson it's from and which errors rustc should give:
+//
+//    // from: ownership::explain_rust_memory
+//    // error: E0382
+//
+//so if a newer compiler starts accepting one (or rejects it for a different reason), this fails.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::{env, fs};
+
+fn fixtures() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("compile_fail");
+    let mut fixtures: Vec<PathBuf> = fs::read_dir(dir)
+        .expect("tests/compile_fail should exist")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .collect();
+    fixtures.sort();
+    fixtures
+}
+
+//The values of every "// <key>: <value>" line in the fixture's header
+fn header(src: &str, key: &str) -> Vec<String> {
+    let prefix = format!("// {key}: ");
+    src.lines()
+        .take_while(|line| line.starts_with("//"))
+        .filter_map(|line| line.strip_prefix(&prefix))
+        .map(|value| value.trim().to_string())
+        .collect()
+}
+
+//Error codes rustc gave, like "E0382"
+fn compile(path: &Path) -> (bool, BTreeSet<String>, String) {
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| String::from("rustc"));
+    let out = Command::new(rustc)
+        .args(["--edition", "2021", "--crate-type", "lib", "--emit", "metadata", "--color", "never"])
+        .arg("--out-dir")
+        .arg(env!("CARGO_TARGET_TMPDIR"))
+        .arg(path)
+        .output()
+        .expect("couldn't run rustc");
+
+    let stderr = String::from_utf8_lossy(&out.stderr).into_owned();
+    let codes = stderr
+        .lines()
+        .filter_map(|line| line.strip_prefix("error[")?.split_once(']'))
+        .map(|(code, _)| code.to_string())
+        .collect();
+    (out.status.success(), codes, stderr)
+}
+
+#[test]
+fn every_fixture_fails_with_its_error_code() {
+    let fixtures = fixtures();
+    assert!(!fixtures.is_empty(), "no fixtures in tests/compile_fail");
+
+    for path in fixtures {
+        let src = fs::read_to_string(&path).unwrap();
+        let expected: BTreeSet<String> = header(&src, "error").into_iter().collect();
+        assert!(!expected.is_empty(), "{} doesn't say which error it expects", path.display());
+
+        let (compiled, codes, stderr) = compile(&path);
+        assert!(!compiled, "{} compiled, but the lesson says it shouldn't", path.display());
+        assert_eq!(codes, expected, "{} failed with different errors:\n{stderr}", path.display());
+    }
+}
+
+#[test]
+fn every_fixture_points_at_a_lesson_function() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+
+    for path in fixtures() {
+        let src = fs::read_to_string(&path).unwrap();
+        let from = header(&src, "from");
+        assert_eq!(from.len(), 1, "{} should have exactly one `// from:` line", path.display());
+
+        let (module, function) = from[0].split_once("::").expect("`from` should look like module::function");
+        let lesson = fs::read_to_string(root.join(format!("{module}.rs"))).unwrap();
+        //a real definition, not one that's commented out
+        let defined = lesson.lines().map(str::trim_start).filter(|l| !l.starts_with("//")).any(|l| {
+            let def = l.strip_prefix("pub ").unwrap_or(l);
+            def.strip_prefix("fn ").and_then(|rest| rest.strip_prefix(function)).is_some_and(|rest| rest.starts_with(['(', '<']))
+        });
+        assert!(defined, "{}: there's no {} in src/{module}.rs", path.display(), from[0]);
+    }
+}
@@ -0,0 +1,12 @@
+// from: ownership_case_study::add_big_strings
+// error: E0502
+// largest borrows dst, so pushing could dealloc the vec and invalidate that borrow
+
+pub fn add_big_strings(dst: &mut Vec<String>, src: &[String]) {
+    let largest: &String = dst.iter().max_by_key(|s| s.len()).unwrap();
+    for s in src {
+        if s.len() > largest.len() {
+            dst.push(s.clone());
+        }
+    }
+}
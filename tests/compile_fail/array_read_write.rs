@@ -0,0 +1,10 @@
+// from: ownership_case_study::safe_but_rejected_array_read_write
+// error: E0502
+// The borrow checker sees a[_] and a[_], not a[0] and a[1]
+
+pub fn safe_but_rejected_array_read_write() {
+    let mut a = [0, 1, 2, 3];
+    let x = &mut a[0];
+    let y = &a[1];
+    *x += *y;
+}
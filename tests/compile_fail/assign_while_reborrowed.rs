@@ -0,0 +1,11 @@
+// from: references_and_borrowing::explain_mutable_references
+// error: E0506
+// *god_ref is borrowed by immutable_god_ref, so it can't be assigned
+
+pub fn explain_mutable_references() {
+    let mut ids: Vec<u64> = vec![91488919, 48199402, 0, 1881002, 777];
+    let god_ref = &mut ids[4];
+    let immutable_god_ref = &*god_ref;
+    *god_ref = 666;
+    println!("Thank our Lord and Savior {immutable_god_ref} for Rust!");
+}
@@ -0,0 +1,10 @@
+// from: references_and_borrowing::explain_data_outliving_references
+// error: E0505
+// The borrow removes oh_no's O permission, but drop requires it
+
+pub fn explain_data_outliving_references() {
+    let oh_no = String::from("I'm in danger.");
+    let danger = &oh_no;
+    drop(oh_no);
+    println!("{}", *danger);
+}
@@ -0,0 +1,13 @@
+// from: references_and_borrowing::explain_data_outliving_references
+// error: E0106
+// From the signature alone, Rust can't tell if the returned &i32 points into im or confused
+
+pub fn flow(im: &Vec<i32>, confused: &i32) -> &i32 {
+    let cond = true;
+
+    if cond {
+        &(**im)[0]
+    } else {
+        confused
+    }
+}
@@ -0,0 +1,14 @@
+// from: ownership_case_study::safe_but_rejected_combine_names
+// error: E0502
+// Rust only looks at get_first's signature, so it thinks all of name is borrowed
+
+fn get_first(name: &(String, String)) -> &String {
+    &name.0
+}
+
+pub fn safe_but_rejected_combine_names() {
+    let mut name = (String::from("Ferris"), String::from("Rustacean"));
+    let first = get_first(&name);
+    name.1.push_str(", Esq");
+    println!("{first} {}", name.1);
+}
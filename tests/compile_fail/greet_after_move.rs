@@ -0,0 +1,14 @@
+// from: references_and_borrowing::explain
+// error: E0382
+// greet_farewell took ownership of both strings, so I can only say hi and bye once
+
+fn greet_farewell(str1: String, str2: String) {
+    let mut combined = str1.clone();
+    combined.push_str(&str2);
+    println!("{combined}!");
+}
+
+pub fn explain(greeting: String, farewell: String) {
+    greet_farewell(greeting, farewell);
+    println!("Good morning, {greeting}, Good night, {farewell}");
+}
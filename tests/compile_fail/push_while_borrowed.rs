@@ -0,0 +1,10 @@
+// from: references_and_borrowing::explain_aliasing
+// error: E0502
+// num is still in use, so pushing (which might reallocate the vector) isn't allowed
+
+pub fn explain_aliasing() {
+    let mut vec: Vec<i32> = vec![1, 2, 3];
+    let num: &i32 = &vec[2];
+    vec.push(4);
+    println!("Third element is {}", *num);
+}
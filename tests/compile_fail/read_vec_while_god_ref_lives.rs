@@ -0,0 +1,12 @@
+// from: references_and_borrowing::explain_mutable_references
+// error: E0502
+// the vector doesn't get its RWO perms back until god_ref is gone
+
+pub fn explain_mutable_references() {
+    let mut ids: Vec<u64> = vec![91488919, 48199402, 0, 1881002, 777];
+    let god_ref = &mut ids[4];
+    let immutable_god_ref = &*god_ref;
+    println!("Did I mention God is {}?", ids[4]);
+    println!("Here, I made a number to represent God: {}", *god_ref);
+    println!("Thank our Lord and Savior {immutable_god_ref} for Rust!");
+}
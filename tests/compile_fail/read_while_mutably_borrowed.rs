@@ -0,0 +1,10 @@
+// from: references_and_borrowing::explain_mutable_references
+// error: E0502
+// num is a mutable reference into ids, so ids has lost its Read permission
+
+pub fn explain_mutable_references() {
+    let mut ids: Vec<u64> = vec![91488919, 48199402, 0, 1881002, 777];
+    let num: &mut u64 = &mut ids[2];
+    println!("Found invalid id: {}", ids[2]);
+    *num = 1;
+}
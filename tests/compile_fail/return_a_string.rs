@@ -0,0 +1,8 @@
+// from: ownership_case_study::return_a_string
+// error: E0106
+// s dies at the end of the function, so there's nothing for the reference to point to
+
+pub fn return_a_string() -> &String {
+    let s = String::from("Hello world");
+    &s
+}
@@ -0,0 +1,10 @@
+// from: ownership_case_study::solution4_1
+// error: E0507
+// Returning *v_ref would need the O permission, which a reference doesn't have
+
+pub fn solution4_1() -> String {
+    let v = vec![String::from("Hello world")];
+    let v_ref = &v[0];
+    println!("{}", v_ref);
+    *v_ref
+}
@@ -0,0 +1,14 @@
+// from: slice::showcase_string_slices
+// error: E0502
+// ecils_backwards is a slice of generic_string, so clearing it while the slice is used isn't allowed
+
+fn gimme_a_slice(s: &String) -> &str {
+    &s[..s.len() / 2]
+}
+
+pub fn showcase_string_slices() {
+    let mut generic_string = String::from("I_can't_think_of_any_other_ways_to_say 'Hello World!'");
+    let ecils_backwards: &str = gimme_a_slice(&generic_string);
+    generic_string.clear();
+    println!("For the people in the back, THIS IS WHAT WE CUT OFF: {ecils_backwards}");
+}
@@ -0,0 +1,9 @@
+// from: ownership_case_study::stringify_name_with_title
+// error: E0596
+// name is an immutable reference, so it doesn't have the W permission push needs
+
+pub fn stringify_name_with_title(name: &Vec<String>) -> String {
+    name.push(String::from("Esq."));
+    let full = name.join(" ");
+    full
+}
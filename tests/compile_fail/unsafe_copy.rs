@@ -0,0 +1,9 @@
+// from: ownership_case_study::unsafe_copy
+// error: E0507
+// The vec owns the string, and ownership can't be taken through a reference
+
+pub fn unsafe_copy() -> String {
+    let v: Vec<String> = vec![String::from("Hello"), String::from("World")];
+    let s_ref: &String = &v[0];
+    *s_ref
+}
@@ -0,0 +1,15 @@
+// from: ownership::explain_rust_memory
+// error: E0382
+// no_im_not's String was moved into add_ten, so no_im_not can't be used anymore
+
+fn add_ten(mut number: String) -> String {
+    number.push_str(" plus ten");
+    number
+}
+
+pub fn explain_rust_memory() {
+    let no_im_not = String::from("nine");
+    let stupid = add_ten(no_im_not);
+    println!("What's {stupid}");
+    println!("{no_im_not}");
+}
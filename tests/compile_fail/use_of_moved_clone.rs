@@ -0,0 +1,17 @@
+// from: ownership::make_it_safe
+// error: E0382
+// The clone was moved into add_ten, so it can't be referenced now
+
+fn add_ten(mut number: String) -> String {
+    number.push_str(" plus ten");
+    number
+}
+
+pub fn make_it_safe() {
+    let quick_maths = String::from("nine");
+    let quick_maths_clone = quick_maths.clone();
+    let im_smart = add_ten(quick_maths_clone);
+    println!("What's {im_smart}?");
+    println!("It's {quick_maths}teen.");
+    println!("{quick_maths_clone}");
+}
@@ -0,0 +1,42 @@
+//Plays "does this compile?" against the real rustc, with a cache dir of its own
+
+mod common;
+
+use common::Sandbox;
+
+fn play(sandbox: &Sandbox, seed: u64, answers: &[&str]) -> String {
+    sandbox.answer(&["--seed", &seed.to_string(), "compiles", "20"], answers)
+}
+
+#[test]
+fn rejected_snippets_point_at_their_case() {
+    let sandbox = Sandbox::new();
+    let out = play(&sandbox, 1, &["n"; 20]);
+
+    assert_eq!(out.matches("does this compile?").count(), 20);
+    let rejected = out.matches("rustc rejects it with E").count();
+    assert!(rejected > 0 && rejected < 20, "20 rounds should have some of both:\n{out}");
+    //only rejections for something the case teaches link to it
+    let explained = out.matches("It's explained in case").count();
+    assert!(explained > 0 && explained <= rejected, "{out}");
+    assert!(out.contains("rejects it with E0507.\n    error[E0507]: cannot move out of `*s_ref`"));
+    assert!(out.ends_with(&format!("You got {rejected} of 20 right.\n")));
+
+    //the same seed plays the same game, this time straight from the cache
+    assert!(sandbox.cache().join("ownership").join("diagnostics").read_dir().unwrap().count() > 1);
+    assert_eq!(play(&sandbox, 1, &["n"; 20]), out);
+}
+
+#[test]
+fn quitting_ends_the_game() {
+    let out = play(&Sandbox::new(), 2, &["y", "q"]);
+    assert!(out.contains("Round 2 of 20") && !out.contains("Round 3 of 20"));
+    assert!(out.trim_end().ends_with("of 1 right."));
+}
+
+#[test]
+fn anything_but_yes_or_no_asks_again() {
+    let out = play(&Sandbox::new(), 2, &["maybe", "y", "q"]);
+    assert!(out.contains("'maybe' isn't y or n (or q to stop playing).\n"));
+    assert!(out.contains("Round 2 of 20") && out.trim_end().ends_with("of 1 right."));
+}
@@ -0,0 +1,18 @@
+//Runs a lesson with the real rustc, then again without one, where every diagnostic has to come from the cache
+
+mod common;
+
+use common::Sandbox;
+
+#[test]
+fn diagnostics_come_from_rustc_then_from_the_cache() {
+    let sandbox = Sandbox::new();
+    let out = sandbox.run(&["--seed", "1", "run", "ownership"]);
+    assert!(out.contains("    rustc says:\n"), "{out}");
+    assert!(out.contains("    | error[E0382]: "), "{out}");
+    assert!(!out.contains("couldn't run rustc"), "{out}");
+
+    //with no rustc to find, the same lesson says the same thing
+    let offline = sandbox.command().env("PATH", "").args(["--seed", "1", "run", "ownership"]).output().expect("couldn't start the ownership binary");
+    assert_eq!(common::stdout(offline, &["run", "ownership"]), out);
+}
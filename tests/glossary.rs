@@ -0,0 +1,43 @@
+//Looks terms up in the glossary and checks they point at lesson functions that exist
+
+mod common;
+
+use common::Sandbox;
+
+fn glossary(args: &[&str]) -> String {
+    Sandbox::new().run(&[&["glossary"], args].concat())
+}
+
+#[test]
+fn every_term_is_introduced_in_a_real_function() {
+    let listed = glossary(&[]);
+    assert!(listed.lines().count() >= 8);
+    for line in listed.lines() {
+        let (term, introduced) = line.rsplit_once(' ').expect("each line is a term and where it's introduced");
+        let out = glossary(&[term.trim()]);
+        let function = introduced.rsplit("::").next().unwrap();
+        let shown = out.split_once(&format!("Introduced in {introduced}:\n")).map(|(_, rest)| rest.lines().next().unwrap_or_default());
+        assert!(shown.is_some_and(|l| l.contains(&format!("fn {function}"))), "{term} doesn't show {introduced}:\n{out}");
+        assert!(out.contains("Run it with `ownership run "));
+        //and no escape codes when it isn't a terminal
+        assert!(!out.contains('\x1b'));
+    }
+}
+
+#[test]
+fn terms_can_be_looked_up_by_other_names() {
+    let out = glossary(&["Fat", "Pointers"]);
+    assert!(out.starts_with("fat pointer\n\nA pointer that carries metadata"));
+    assert!(out.contains("Run it with `ownership run slice`\nSee also: slice\n"));
+
+    let out = glossary(&["mutable", "reference"]);
+    assert!(out.starts_with("unique reference\n"));
+    assert!(out.contains("Introduced in references_and_borrowing::explain_mutable_references:"));
+
+    let out = glossary(&["ref"]);
+    assert!(out.contains("could be any of: reference, dereference, unique reference"));
+
+    let out = Sandbox::new().output(&["glossary", "garbage"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("'garbage' isn't in the glossary"));
+}
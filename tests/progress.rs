@@ -0,0 +1,156 @@
+//Finishes lessons and answers questions as one learner, in a data dir of its own, and checks what gets remembered
+
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+use common::Sandbox;
+
+fn ownership(sandbox: &Sandbox, args: &[&str], answers: &[&str]) -> String {
+    sandbox.answer(&[&["--learner", "ferris", "--no-diagnostics"], args].concat(), answers)
+}
+
+fn progress_file(sandbox: &Sandbox) -> PathBuf {
+    sandbox.data().join("ownership").join("progress.txt")
+}
+
+#[test]
+fn the_menu_resumes_after_the_last_lesson() {
+    let sandbox = Sandbox::new();
+    ownership(&sandbox, &["run", "ownership"], &[]);
+
+    let saved = fs::read_to_string(progress_file(&sandbox)).unwrap();
+    assert!(saved.starts_with("ownership progress v2\nlesson\tferris\townership\t1\t"), "{saved}");
+
+    let out = ownership(&sandbox, &[], &["q"]);
+    assert!(out.starts_with("Welcome back, ferris! Last time you finished ownership (today).\n"));
+    //vectors comes next on the recommended path, since references_and_borrowing builds on it
+    assert!(out.contains("press enter to resume with vectors, or q to quit:"));
+    assert!(out.contains("  1. ownership - What ownership is and how moves work (done)\n"));
+
+    //someone else starts from scratch
+    let out = ownership(&sandbox, &["--learner", "crab"], &["q"]);
+    assert!(out.starts_with("Pick a lesson (number or name), or q to quit:"));
+}
+
+#[test]
+fn mastered_lessons_are_skipped_when_resuming() {
+    let sandbox = Sandbox::new();
+    ownership(&sandbox, &["run", "vectors"], &[]);
+    let out = ownership(&sandbox, &["quiz", "vectors"], &["true", "b", "7", "false"]);
+    assert!(out.ends_with("You got 4 of 4 right (100%).\n"));
+
+    let saved = fs::read_to_string(progress_file(&sandbox)).unwrap();
+    assert!(saved.contains("answer\tferris\tvectors::weekdays_len\t1\t1\t1\t"));
+    //right the first time: due again in a day
+    let record = saved.lines().find(|l| l.contains("vectors::weekdays_len")).unwrap();
+    let fields: Vec<&str> = record.split('\t').collect();
+    assert_eq!(&fields[7..10], ["250", "1", "1"]);
+
+    //run --all still runs everything
+    let out = ownership(&sandbox, &["run", "--all"], &[]);
+    assert!(out.contains("=== vectors ===") && out.contains("=== slice ==="));
+    assert!(!out.contains("mastered"));
+
+    //but resuming skips past what's mastered
+    let out = ownership(&sandbox, &[], &["q"]);
+    assert!(out.contains("  2. vectors - Vectors and how they grow (mastered)\n"));
+    assert!(out.contains("press enter to resume with ownership,"));
+}
+
+#[test]
+fn jumping_ahead_offers_the_prerequisites_first() {
+    let sandbox = Sandbox::new();
+    ownership(&sandbox, &["run", "ownership"], &[]);
+
+    let out = ownership(&sandbox, &[], &["slice", "y", "q"]);
+    assert!(out.contains("slice builds on vectors, references_and_borrowing, which you haven't done yet.\nRun them first? (y or n, enter for yes)\n"));
+    let vectors = out.find("=== vectors ===").expect("vectors should run first");
+    let references = out.find("=== references_and_borrowing ===").expect("references_and_borrowing should run next");
+    let slice = out.find("=== slice ===").expect("slice should run last");
+    assert!(vectors < references && references < slice);
+
+    //saying no runs just the lesson that was picked
+    let out = ownership(&sandbox, &[], &["ownership_case_study::case2", "n", "q"]);
+    assert!(!out.contains("builds on"), "everything case2 needs is done by now");
+    assert!(out.contains("=== ownership_case_study::case2 ==="));
+    let out = ownership(&sandbox, &["--learner", "crab"], &["ownership_case_study::case2", "n", "q"]);
+    assert!(out.contains("ownership_case_study::case2 builds on ownership, vectors, references_and_borrowing, which you haven't done yet."));
+    assert!(out.contains("=== ownership_case_study::case2 ===") && !out.contains("=== ownership ==="));
+}
+
+#[test]
+fn a_file_from_a_newer_version_is_left_alone() {
+    let sandbox = Sandbox::new();
+    let file = progress_file(&sandbox);
+    fs::create_dir_all(file.parent().unwrap()).unwrap();
+    fs::write(&file, "ownership progress v99\nsomething new\n").unwrap();
+
+    let out = sandbox.output(&["--no-diagnostics", "run", "vectors"]);
+    assert!(out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("it's version 99, from a newer version of this program"));
+    assert_eq!(fs::read_to_string(&file).unwrap(), "ownership progress v99\nsomething new\n");
+}
+
+#[test]
+fn missed_questions_come_back_for_review() {
+    let sandbox = Sandbox::new();
+    let out = ownership(&sandbox, &["review"], &[]);
+    assert!(out.contains("Nothing to review: answer some questions with `ownership quiz` first."));
+
+    //two right, two wrong
+    ownership(&sandbox, &["quiz", "vectors"], &["true", "b", "6", "true"]);
+    let out = ownership(&sandbox, &["review"], &["7", "q"]);
+    assert!(out.starts_with("2 questions to review.\n\nQuestion 1 of 2 (vectors)\nHow long is weekdays at the end?"));
+    assert!(out.contains("Right! Five to start with"));
+    assert!(out.contains("Question 2 of 2 (vectors)\nAn array like [char; 5] can grow with push too."));
+    assert!(out.ends_with("You got 1 of 1 right (100%).\nNext review: now.\n"));
+
+    let out = ownership(&sandbox, &["review"], &["false"]);
+    assert!(out.starts_with("1 question to review."));
+    assert!(out.ends_with("Next review: tomorrow.\n"));
+    let out = ownership(&sandbox, &["review"], &[]);
+    assert_eq!(out, "Nothing to review yet. The next question is due tomorrow.\n");
+}
+
+#[test]
+fn version_1_files_get_a_review_schedule() {
+    let sandbox = Sandbox::new();
+    let file = progress_file(&sandbox);
+    fs::create_dir_all(file.parent().unwrap()).unwrap();
+    let v1 = "ownership progress v1\n\
+              lesson\tferris\tvectors\t2\t1700000000\n\
+              answer\tferris\tvectors::on_the_heap\t3\t2\t1\t1700000000\n\
+              answer\tferris\tvectors::array_push\t1\t0\t0\t1700000000\n";
+    fs::write(&file, v1).unwrap();
+
+    //the one answered wrong is due, the other one was due a day after it was answered
+    let out = ownership(&sandbox, &["review"], &["q"]);
+    assert!(out.starts_with("2 questions to review.\n\nQuestion 1 of 2 (vectors)\nAn array like [char; 5]"));
+
+    let saved = fs::read_to_string(&file).unwrap();
+    assert_eq!(
+        saved,
+        "ownership progress v2\n\
+         lesson\tferris\tvectors\t2\t1700000000\n\
+         answer\tferris\tvectors::on_the_heap\t3\t2\t1\t1700000000\t250\t1\t1\t1700086400\n\
+         answer\tferris\tvectors::array_push\t1\t0\t0\t1700000000\t250\t0\t0\t1700000000\n"
+    );
+}
+
+#[test]
+fn review_intervals_stop_growing_after_a_year() {
+    let sandbox = Sandbox::new();
+    let file = progress_file(&sandbox);
+    fs::create_dir_all(file.parent().unwrap()).unwrap();
+    //answered right so many times that the next interval would be 625 million days
+    let v2 = "ownership progress v2\n\
+              answer\tferris\tvectors::weekdays_len\t40\t40\t1\t1700000000\t250\t40\t250000000\t0\n";
+    fs::write(&file, v2).unwrap();
+
+    let out = ownership(&sandbox, &["review"], &["7"]);
+    assert!(out.ends_with("Next review: in 365 days.\n"), "{out}");
+    let saved = fs::read_to_string(&file).unwrap();
+    let fields: Vec<&str> = saved.lines().nth(1).unwrap().split('\t').collect();
+    assert_eq!(&fields[7..10], ["250", "41", "365"]);
+}
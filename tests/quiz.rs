@@ -0,0 +1,74 @@
+//Takes quizzes with answers written down ahead of time and checks the feedback and the score
+
+mod common;
+
+use common::Sandbox;
+
+fn quiz(args: &[&str], answers: &[&str]) -> String {
+    Sandbox::new().answer(&[&["quiz"], args].concat(), answers)
+}
+
+#[test]
+fn every_kind_of_answer_gets_feedback_and_a_score() {
+    //a letter, true/false, a number, free text (any case), and a wrong one
+    let out = quiz(&["ownership"], &["c", "true", "2", "e0382", "yes"]);
+    assert!(out.contains("Question 1 of 5 (ownership)"));
+    assert!(out.contains("  d) it prints whatever is left in freed memory\nRight! add_ten took ownership"));
+    assert_eq!(out.matches("Right!").count(), 4);
+    assert!(out.contains("Not quite: it's false. Ownership is checked at compile time"));
+    assert!(out.ends_with("You got 4 of 5 right (80%).\n"));
+}
+
+#[test]
+fn quitting_early_scores_what_was_answered() {
+    let out = quiz(&[], &["c", "false", "q"]);
+    assert!(out.contains("Question 1 of 33 (ownership)"));
+    assert!(out.contains("Question 3 of 33") && !out.contains("Question 4 of 33"));
+    assert!(out.ends_with("You got 1 of 2 right (50%).\n"));
+}
+
+#[test]
+fn every_module_has_questions() {
+    let listed = quiz(&["list"], &[]);
+    for module in ["ownership", "references_and_borrowing", "slice", "vectors", "ownership_case_study"] {
+        assert!(listed.lines().any(|l| l.starts_with(module) && !l.ends_with(" 0 questions")), "{module} has no questions");
+    }
+
+    let out = Sandbox::new().output(&["quiz", "borrowing"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("there are no questions about 'borrowing'"));
+}
+
+#[test]
+fn predicting_output_is_checked_against_what_the_lesson_printed() {
+    let mut answers = vec!["x"; 8];
+    answers.push("A: 2, b: 1, c: 2,  d: 2, e: 2, f: 2");
+    let out = quiz(&["references_and_borrowing"], &answers);
+    assert!(out.contains("Question 9 of 9 (references_and_borrowing)\nWhat does the say! line print?\n\n    fn explain_dereferencing("));
+    assert!(out.contains("Right! That's what references_and_borrowing printed when it ran."));
+
+    let mut answers = vec!["x"; 8];
+    answers.push("1");
+    let out = quiz(&["ownership_case_study"], &answers);
+    assert!(out.contains("    fn safe_copy() -> i32 {"));
+    assert!(out.contains("Not quite: it's 0. That's what ownership_case_study::case4 printed when it ran."));
+}
+
+#[test]
+fn anki_export_has_a_note_per_question_and_term_tagged_by_module() {
+    let sandbox = Sandbox::new();
+    let file = sandbox.path("anki.txt");
+    assert!(sandbox.run(&["anki", &file]).starts_with("Wrote 50 notes to "));
+
+    let text = std::fs::read_to_string(&file).expect("the export should be written");
+    let notes: Vec<&str> = text.lines().filter(|l| !l.starts_with('#')).collect();
+    assert_eq!(notes.len(), 50);
+    assert!(notes.iter().all(|n| n.split('\t').count() == 3));
+    let deref = notes.iter().find(|n| n.starts_with("What does **c evaluate to?")).expect("double_deref should be exported");
+    assert!(deref.contains("<pre>let mut a: Box&lt;i32&gt; = Box::new(1);<br>let b: i32 = *a;"));
+    assert!(deref.ends_with("\t<b>2</b><br><br>c points to a on the stack, a points to the heap: *c is a, and **c is the 2 that *a += 1 left there.\treferences_and_borrowing"));
+    assert!(notes.iter().any(|n| n.ends_with("\tslice")));
+    let copy = notes.iter().find(|n| n.starts_with("Copy trait\t")).expect("glossary terms should be exported");
+    assert!(copy.contains("<pre>let n_ref: &amp;i32 = &amp;v[0];<br>"));
+    assert!(copy.ends_with("<i>Introduced in ownership_case_study::safe_copy</i>\tglossary ownership_case_study"));
+}
@@ -0,0 +1,34 @@
+//Searches the lessons, glossary and quiz and checks where the hits point
+
+mod common;
+
+use common::Sandbox;
+
+fn search(query: &[&str]) -> String {
+    Sandbox::new().run(&[&["search"], query].concat())
+}
+
+#[test]
+fn realloc_finds_where_memory_gets_moved_and_freed() {
+    let out = search(&["realloc"]);
+    //"dealloc'd", "deallocs" and "new alloc" all count
+    assert!(out.contains(". ownership::add_ten  (`ownership run ownership`)\n      comment  3: this creates a new heap alloc"));
+    assert!(out.contains(". references_and_borrowing::explain_aliasing  (`ownership run references_and_borrowing`)"));
+    assert!(out.contains(" 1. ownership::explain_rust_memory  (`ownership run ownership`)\n      prints   push_str reallocs"));
+    assert!(out.contains("quiz ownership::push_str_reallocs  (`ownership quiz ownership`)"));
+}
+
+#[test]
+fn hits_point_at_the_lesson_that_runs_them() {
+    let out = search(&["fat", "pointers"]);
+    assert!(out.starts_with("\"fat pointers\" comes up in 2 places:\n\n 1. slice::showcase_string_slices  (`ownership run slice`)"));
+    assert!(out.contains("      glossary fat pointer (fat pointers): A pointer that carries metadata"));
+
+    let out = search(&["garbage", "collection"]);
+    assert!(out.contains("ownership::the_alternative  (`ownership run ownership::recap`)"));
+
+    let out = search(&["Copy"]);
+    assert!(out.contains("ownership_case_study::safe_copy  (`ownership run ownership_case_study::case4`)"));
+
+    assert_eq!(search(&["xylophone"]), "Nothing about \"xylophone\" in the lessons, glossary or quiz.\n");
+}
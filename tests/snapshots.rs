@@ -0,0 +1,118 @@
+//Runs every lesson and compares what it printed with the transcripts in tests/snapshots.
+//After changing a lesson on purpose, rerun with UPDATE_SNAPSHOTS=1 to rewrite the transcripts.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+use common::Sandbox;
+
+//Every lesson runs with this seed. Lessons that branch on a random choice get extra runs
+//with other seeds so every branch has a snapshot
+const SEED: u64 = 1;
+const EXTRA_SEEDS: [(&str, u64); 1] = [
+    //explain_lifetimes picks a positive number with this seed (and a negative one with SEED)
+    ("references_and_borrowing", 2),
+];
+
+fn ownership(seed: u64, args: &[&str]) -> String {
+    let answers = fs::read_to_string(snapshot_dir().join("answers.txt")).expect("couldn't read the answers");
+    let answers: Vec<&str> = answers.lines().collect();
+    //what rustc prints changes between versions, so only the expected error codes go in snapshots
+    let seed = seed.to_string();
+    let args = [&["--seed", &seed, "--no-diagnostics"], args].concat();
+    //finishing a lesson records progress, which goes in the sandbox
+    Sandbox::new().answer(&args, &answers)
+}
+
+fn snapshot_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("snapshots")
+}
+
+//"ownership_case_study::case3" -> "ownership_case_study__case3.txt",
+//and runs with an extra seed get it in the name: "references_and_borrowing.seed2.txt"
+fn snapshot_path(lesson: &str, seed: u64) -> PathBuf {
+    let name = lesson.replace("::", "__");
+    match seed {
+        SEED => snapshot_dir().join(format!("{name}.txt")),
+        _ => snapshot_dir().join(format!("{name}.seed{seed}.txt")),
+    }
+}
+
+//Every (lesson, seed) pair that should have a snapshot
+fn runs() -> Vec<(String, u64)> {
+    let mut runs: Vec<(String, u64)> = lessons().into_iter().map(|l| (l, SEED)).collect();
+    runs.extend(EXTRA_SEEDS.iter().map(|&(l, seed)| (l.to_string(), seed)));
+    runs
+}
+
+fn lessons() -> Vec<String> {
+    ownership(SEED, &["list"])
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(String::from)
+        .collect()
+}
+
+//Points at the first line that changed, which is usually all you need to see
+fn first_difference(expected: &str, actual: &str) -> String {
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    for n in 1.. {
+        match (expected_lines.next(), actual_lines.next()) {
+            (None, None) => break,
+            (e, a) if e == a => continue,
+            (e, a) => return format!("line {n}:\n  expected: {e:?}\n  actual:   {a:?}"),
+        }
+    }
+    String::from("(only whitespace at the end differs)")
+}
+
+#[test]
+fn every_lesson_matches_its_snapshot() {
+    let update = env::var_os("UPDATE_SNAPSHOTS").is_some();
+    let mut failures = Vec::new();
+
+    for (lesson, seed) in runs() {
+        let actual = ownership(seed, &["run", &lesson]);
+        let path = snapshot_path(&lesson, seed);
+
+        if update {
+            fs::write(&path, &actual).expect("couldn't write snapshot");
+            continue;
+        }
+
+        match fs::read_to_string(&path) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) => failures.push(format!("{lesson} (seed {seed}) changed, {}", first_difference(&expected, &actual))),
+            Err(_) => failures.push(format!("{lesson} (seed {seed}) has no snapshot at {}", path.display())),
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{}\n\nif these changes are on purpose, rerun with UPDATE_SNAPSHOTS=1",
+        failures.join("\n\n")
+    );
+}
+
+#[test]
+fn snapshots_belong_to_real_lessons() {
+    let expected: Vec<PathBuf> = runs().iter().map(|(lesson, seed)| snapshot_path(lesson, *seed)).collect();
+    for entry in fs::read_dir(snapshot_dir()).unwrap() {
+        let path = entry.unwrap().path();
+        if path.file_name().unwrap() == "answers.txt" {
+            continue;
+        }
+        assert!(expected.contains(&path), "{} doesn't belong to any lesson", path.display());
+    }
+}
+
+#[test]
+fn both_branches_of_explain_lifetimes_are_covered() {
+    let seeds = [SEED, EXTRA_SEEDS[0].1];
+    let outputs: Vec<String> = seeds.iter().map(|&seed| ownership(seed, &["run", "references_and_borrowing"])).collect();
+
+    assert!(outputs.iter().any(|out| out.contains("to be negative (now")), "no seed takes the positive branch");
+    assert!(outputs.iter().any(|out| out.contains("is already negative")), "no seed takes the negative branch");
+}
@@ -0,0 +1,243 @@
+//Runs the toy language's examples and checks each one ends the way the lesson it comes from says it should
+
+mod common;
+
+use std::fs;
+use common::Sandbox;
+
+fn toy(args: &[&str]) -> String {
+    Sandbox::new().run(&[&["toy"], args].concat())
+}
+
+const ENDINGS: [(&str, &str); 11] = [
+    ("add_ten", "use of moved value: no_im_not was moved on line 3"),
+    ("make_it_safe", "Finished without any undefined behavior"),
+    ("safe_copy", "Finished without any undefined behavior"),
+    ("unsafe_copy", "double free: #2 frees #0"),
+    ("dereferencing", "Finished without any undefined behavior"),
+    ("aliasing", "use after free: #0 was freed on line 4"),
+    ("aliasing_fixed", "Finished without any undefined behavior"),
+    ("lifetimes", "Finished without any undefined behavior"),
+    ("tuple_fields", "Finished without any undefined behavior"),
+    ("get_first", "Finished without any undefined behavior"),
+    ("array_read_write", "prints: [1, 1, 2, 3]"),
+];
+
+#[test]
+fn every_example_ends_the_way_its_lesson_says() {
+    let listed = toy(&["list"]);
+    for (example, ending) in ENDINGS {
+        assert!(listed.contains(example), "{example} isn't in `toy list`");
+        let out = toy(&[example]);
+        assert!(out.contains(ending), "{example} should end with `{ending}`, but printed:\n{out}");
+    }
+    assert_eq!(listed.lines().count(), ENDINGS.len(), "every example needs an expected ending here");
+}
+
+#[test]
+fn dereferencing_follows_the_box_chain() {
+    assert!(toy(&["dereferencing"]).contains("prints: a: 2, b: 1, c: 2, d: 2, e: 2, f: 2"));
+}
+
+#[test]
+fn unsafe_copy_leaves_two_owners() {
+    let out = toy(&["unsafe_copy"]);
+    assert!(out.contains("so it has two owners now"));
+    assert!(out.contains("#0  \"Hello\"               (freed)"));
+}
+
+#[test]
+fn push_is_rejected_only_while_num_is_live() {
+    let rejected = toy(&["check", "aliasing"]);
+    assert!(rejected.contains("error[E0502]: cannot borrow vec as mutable because it is also borrowed as immutable"));
+    //points at the borrow and at the later use that keeps it alive
+    assert!(rejected.contains("vec[_] is borrowed immutably here (by num)"));
+    assert!(rejected.contains("line 5   println!(\"Third element is {}\", *num);\n            num is used later here"));
+
+    let accepted = toy(&["check", "aliasing_fixed"]);
+    assert!(accepted.contains("No borrow errors."));
+    assert!(accepted.contains("&vec[_]              held by num, lines 3, 4"));
+}
+
+#[test]
+fn conditional_ref_only_borrows_inside_the_if() {
+    let out = toy(&["check", "lifetimes"]);
+    assert!(out.contains("No borrow errors."));
+    assert!(out.contains("&mut original[_]     held by ref_1, ref_2, lines 3, 4, 5, 6"));
+    assert!(out.contains("&mut pos_or_neg[_]   held by conditional_ref, lines 13, 14, 15"));
+}
+
+#[test]
+fn the_checker_agrees_with_the_interpreter() {
+    assert!(toy(&["check", "add_ten"]).contains("error[E0382]: use of moved value: no_im_not"));
+    assert!(toy(&["check", "unsafe_copy"]).contains("error[E0507]: cannot move out of *s_ref"));
+    for example in ["make_it_safe", "safe_copy", "dereferencing"] {
+        assert!(toy(&["check", example]).contains("No borrow errors."), "{example} should pass the checker");
+    }
+}
+
+#[test]
+fn rustc_precision_rejects_safe_index_borrows() {
+    let out = toy(&["check", "array_read_write"]);
+    assert!(out.contains("error[E0502]: cannot borrow a[_] as immutable because it is also borrowed as mutable"));
+    assert!(out.contains("   4      let y = &a[1];             E0502    ok\n"));
+    assert!(out.contains("This program is safe, but rustc rejects it because it can't tell indexes apart:\n  line 4:"));
+
+    //different tuple fields were never a problem, and really unsafe programs stay rejected
+    for example in ["tuple_fields", "aliasing"] {
+        assert!(toy(&["check", example]).contains("gives the same answer"), "{example} shouldn't depend on precision");
+    }
+}
+
+#[test]
+fn get_first_is_only_rejected_when_checked_by_its_signature() {
+    let out = toy(&["check", "get_first"]);
+    assert!(out.contains("error[E0502]: cannot borrow name.1 as mutable because it is also borrowed as immutable"));
+    assert!(out.contains("name is borrowed immutably here (by first)"));
+    assert!(out.contains("rustc next to looking inside the functions it calls:"));
+    assert!(out.contains("   4      name.1.push_str(\", Esq\");") && out.contains("E0502    ok\n"));
+    assert!(out.contains("This program is safe, but rustc rejects it because it only looks at function signatures:"));
+}
+
+//Writes a program of its own for a test, and returns the path to give `toy`
+fn program(sandbox: &Sandbox, name: &str, src: &str) -> String {
+    let path = sandbox.path(&format!("{name}.toy"));
+    fs::write(&path, src).expect("couldn't write the program");
+    path
+}
+
+//returns a reference to its own local
+const DANGLE: &str = "fn main() {\n    let r = dangle();\n    println!(\"{r}\");\n}\n\nfn dangle() -> &String {\n    let s = String::from(\"hi\");\n    &s\n}\n";
+
+#[test]
+fn the_rustc_note_comes_from_what_the_checker_found() {
+    assert!(toy(&["aliasing"]).contains("(rustc would have refused to compile this: E0502 on line 4, cannot borrow vec as mutable"));
+    let sandbox = Sandbox::new();
+    let dangle = program(&sandbox, "dangle", DANGLE);
+    let out = toy(&[&dangle]);
+    assert!(out.contains("line 3: dangling reference"), "{out}");
+    assert!(out.contains("(rustc would have refused to compile this: E0515 on line 8, cannot return a reference to s"), "{out}");
+}
+
+#[test]
+fn stepping_past_a_returned_frame_draws_the_reference_as_gone() {
+    let sandbox = Sandbox::new();
+    let dangle = program(&sandbox, "dangle_step", DANGLE);
+    let out = step(&dangle, &["next", "next", "next", "next", "next", "quit"]);
+    assert!(out.contains("--> &(gone)"), "{out}");
+    assert!(out.contains("That's as far as it goes. Line 3 can't run: dangling reference"), "{out}");
+}
+
+#[test]
+fn runaway_recursion_is_an_error_not_a_crash() {
+    let sandbox = Sandbox::new();
+    let endless = program(&sandbox, "endless", "fn main() {\n    f();\n}\n\nfn f() {\n    f();\n}\n");
+    assert!(toy(&[&endless]).contains("line 6: calling f goes more than 100 calls deep"));
+    assert!(step(&endless, &["quit"]).contains("Line 6 can't run: calling f goes more than 100 calls deep"));
+}
+
+#[test]
+fn assigning_to_a_whole_vec_is_an_error_not_a_crash() {
+    let sandbox = Sandbox::new();
+    let whole = program(&sandbox, "whole_vec", "fn main() {\n    let mut v = vec![1, 2];\n    *v = 5;\n}\n");
+    assert!(toy(&[&whole]).contains("line 3: can't assign to a whole Vec, only to its elements"));
+}
+
+#[test]
+fn a_huge_repeat_array_is_an_error_not_a_crash() {
+    let sandbox = Sandbox::new();
+    let huge = program(&sandbox, "huge_array", "fn main() {\n    let x = [1; 100000000000];\n}\n");
+    let error = "line 2: [_; 100000000000] is too long, the toy language only goes up to 1000 elements";
+    assert!(toy(&[&huge]).contains(error));
+    assert!(toy(&["check", &huge]).contains(error));
+    assert!(step(&huge, &["quit"]).contains(error));
+}
+
+#[test]
+fn negating_the_smallest_i64_wraps_like_a_release_build() {
+    let sandbox = Sandbox::new();
+    let min = program(&sandbox, "min", "fn main() {\n    let a = -9223372036854775807;\n    let b = a - 1;\n    let c = -b;\n    let d = b.abs();\n    println!(\"{c} {d}\");\n}\n");
+    assert!(toy(&[&min]).contains("prints: -9223372036854775808 -9223372036854775808"));
+}
+
+#[test]
+fn looking_inside_a_recursive_function_stops_at_its_signature() {
+    let sandbox = Sandbox::new();
+    let recursive = program(&sandbox, "recursive_ref", "fn main() {\n    let x = 5;\n    let r = f(&x);\n    println!(\"{r}\");\n}\n\nfn f(x: &i32) -> &i32 {\n    f(x)\n}\n");
+    let out = toy(&["check", &recursive]);
+    assert!(out.contains("Looking inside the functions it calls gives the same answer."), "{out}");
+    assert!(out.contains("&x                   held by r, lines 3, 4"), "{out}");
+}
+
+//Steps through an example, answering the prompts with `commands`
+fn step(example: &str, commands: &[&str]) -> String {
+    Sandbox::new().answer(&["toy", "step", example], commands)
+}
+
+#[test]
+fn stepping_follows_the_box_chain() {
+    let out = step("dereferencing", &["next", "next", "next", "inspect c", "next", "next", "next", "next", "inspect e", "back", "quit"]);
+    assert!(out.contains("--- step 4 of 11 ---"));
+    assert!(out.contains("c --> a --> #0 Box(2) --> 2"));
+    assert!(out.contains("  holds &a (alive until its last use)"));
+    assert!(out.contains("    a                R    borrowed by c"));
+
+    //after the println nothing uses c or e again, so a gets everything back
+    let last = out.split("--- step 8 of 11 ---").nth(1).unwrap();
+    assert!(last.contains("    a                RWO  \n"));
+    assert!(last.contains("e --> #0 --> 2\n  e                -    dead: not used again"));
+    assert_eq!(out.matches("--- step 7 of 11 ---").count(), 2, "back should show step 7 again");
+}
+
+#[test]
+fn stepping_shows_ref_1_handing_off_to_ref_2() {
+    let out = step("lifetimes", &["next", "next", "next", "inspect ref_1", "next", "inspect original", "quit"]);
+    let handoff = out.split("--- step 4 of").nth(1).unwrap();
+    assert!(handoff.contains("    &mut original[_]     held by ref_1, ref_2"));
+    assert!(handoff.contains("    ref_1            -    dead: not used again"));
+    assert!(handoff.contains("    original         -    borrowed by ref_1, ref_2"));
+
+    //once ref_2 has printed, original can be written again
+    let after = out.split("--- step 5 of").nth(1).unwrap();
+    assert!(after.contains("  live borrows\n    (none)"));
+    assert!(after.contains("original --> #0 Box([1, 5, 1]) --> [1, 5, 1]\n  original         RWO"));
+}
+
+#[test]
+fn dot_export_draws_the_box_chain() {
+    let sandbox = Sandbox::new();
+    let dir = sandbox.path("dot");
+    sandbox.answer(&["--dot", &dir, "toy", "step", "dereferencing"], &["next"; 7]);
+
+    //step 8 is the println: c --> a --> #0 and e --> #0
+    let graph = fs::read_to_string(format!("{dir}/008-step8.dot")).expect("step 8 should have a .dot file");
+    assert!(graph.starts_with("digraph memory {"));
+    assert!(graph.contains("frame0:s0:c -> heap0 [tailclip=false];"), "a owns the Box:\n{graph}");
+    assert!(graph.contains("frame0:s2:c -> frame0:s0:w [tailclip=false, style=dashed, color=blue];"), "c points at a:\n{graph}");
+    assert!(graph.contains("frame0:s4:c -> heap0 [tailclip=false, style=dashed, color=blue];"), "e points into the Box:\n{graph}");
+    assert!(graph.contains("heap0 [shape=box, label=\"#0  Box(2)\"];"));
+}
+
+#[test]
+fn dot_export_draws_every_statement_of_a_run() {
+    let sandbox = Sandbox::new();
+    let dir = sandbox.path("dot");
+    sandbox.run(&["--dot", &dir, "toy", "dereferencing"]);
+
+    let files = fs::read_dir(&dir).map(|d| d.count()).unwrap_or(0);
+    let graph = fs::read_to_string(format!("{dir}/008-step8.dot")).expect("step 8 should have a .dot file");
+    assert_eq!(files, 11, "one file per statement, even though nothing went wrong");
+    assert!(graph.contains("frame0:s2:c -> frame0:s0:w [tailclip=false, style=dashed, color=blue];"), "c points at a:\n{graph}");
+    assert!(graph.contains("frame0:s0:c -> heap0 [tailclip=false];"), "a owns the Box:\n{graph}");
+}
+
+#[test]
+fn dot_export_greys_out_moved_slots() {
+    let sandbox = Sandbox::new();
+    let dir = sandbox.path("dot");
+    sandbox.run(&["--dot", &dir, "toy", "add_ten"]);
+
+    //six statements ran before line 5 went wrong, then that gets drawn too
+    let graph = fs::read_to_string(format!("{dir}/007-step5.dot")).expect("the use after move should be drawn");
+    assert!(graph.contains("<td bgcolor=\"lightgrey\"><font color=\"grey40\">no_im_not</font></td>"), "{graph}");
+}